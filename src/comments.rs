@@ -0,0 +1,248 @@
+use std::path::Path;
+
+/// Comment syntax of a language, used to detect directives and the comments
+/// attached to the sorted items.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CommentSyntax {
+    /// Prefixes of single-line comments, eg. `//`.
+    pub line: &'static [&'static str],
+    /// Delimiters of block comments written on a single line, eg. `/*` and `*/`.
+    pub block: &'static [(&'static str, &'static str)],
+    /// Prefixes of lines that are not comments but stay with the item below
+    /// them like comments, eg. Rust attributes.
+    pub attached: &'static [&'static str],
+}
+
+const HASH: CommentSyntax = CommentSyntax {
+    line: &["#"],
+    block: &[],
+    attached: &[],
+};
+
+const C_LIKE: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    attached: &[],
+};
+
+const RUST: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    attached: &["#[", "#!["],
+};
+
+const CSS: CommentSyntax = CommentSyntax {
+    line: &[],
+    block: &[("/*", "*/")],
+    attached: &[],
+};
+
+const SCSS: CommentSyntax = CommentSyntax {
+    line: &["//"],
+    block: &[("/*", "*/")],
+    attached: &[],
+};
+
+const SQL: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    block: &[("/*", "*/")],
+    attached: &[],
+};
+
+const LUA: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    block: &[],
+    attached: &[],
+};
+
+const HASKELL: CommentSyntax = CommentSyntax {
+    line: &["--"],
+    block: &[("{-", "-}")],
+    attached: &[],
+};
+
+const LISP: CommentSyntax = CommentSyntax {
+    line: &[";;", ";"],
+    block: &[],
+    attached: &[],
+};
+
+const INI: CommentSyntax = CommentSyntax {
+    line: &[";", "#"],
+    block: &[],
+    attached: &[],
+};
+
+const TEX: CommentSyntax = CommentSyntax {
+    line: &["%"],
+    block: &[],
+    attached: &[],
+};
+
+const MARKUP: CommentSyntax = CommentSyntax {
+    line: &[],
+    block: &[("<!--", "-->")],
+    attached: &[],
+};
+
+const BATCH: CommentSyntax = CommentSyntax {
+    line: &["REM", "rem", "::"],
+    block: &[],
+    attached: &[],
+};
+
+/// Comment syntaxes by file extension. Files with other extensions use the
+/// default syntax.
+const SYNTAX_BY_EXTENSION: &[(&[&str], CommentSyntax)] = &[
+    (&["bat", "cmd"], BATCH),
+    (&["bazel", "bzl", "star"], HASH),
+    (
+        &[
+            "c", "cc", "cpp", "cs", "dart", "go", "h", "hpp", "java", "js", "jsx", "kt", "proto",
+            "scala", "swift", "ts", "tsx",
+        ],
+        C_LIKE,
+    ),
+    (&["clj", "cljs", "el", "lisp", "scm"], LISP),
+    (&["css"], CSS),
+    (&["erl", "hrl", "sty", "tex"], TEX),
+    (&["hs"], HASKELL),
+    (
+        &["htm", "html", "markdown", "md", "svg", "vue", "xml"],
+        MARKUP,
+    ),
+    (&["ini"], INI),
+    (&["less", "sass", "scss"], SCSS),
+    (&["lua"], LUA),
+    (&["py", "rb", "sh", "toml", "yaml", "yml"], HASH),
+    (&["rs"], RUST),
+    (&["sql"], SQL),
+];
+
+/// Comment syntaxes by file name, for files without an extension. They are
+/// looked up before the extension.
+const SYNTAX_BY_FILE_NAME: &[(&[&str], CommentSyntax)] = &[(
+    &[
+        ".dockerignore",
+        ".gitignore",
+        ".ignore",
+        ".npmignore",
+        ".prettierignore",
+        "BUILD",
+        "CODEOWNERS",
+        "WORKSPACE",
+    ],
+    HASH,
+)];
+
+impl Default for CommentSyntax {
+    /// `#` and `//` comments, as in most configuration files and C-like languages.
+    fn default() -> Self {
+        CommentSyntax {
+            line: &["#", "//"],
+            block: &[],
+            attached: &[],
+        }
+    }
+}
+
+impl CommentSyntax {
+    /// Picks the comment syntax of the file from its name or its extension.
+    /// The file does not need to exist.
+    pub fn for_path(path: &Path) -> CommentSyntax {
+        let by_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| {
+                SYNTAX_BY_FILE_NAME
+                    .iter()
+                    .find(|(names, _)| names.contains(&name))
+            });
+        let by_extension = || {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(|ext| {
+                    SYNTAX_BY_EXTENSION
+                        .iter()
+                        .find(|(extensions, _)| extensions.contains(&ext.to_lowercase().as_str()))
+                })
+        };
+        by_name
+            .or_else(by_extension)
+            .map_or_else(CommentSyntax::default, |(_, syntax)| *syntax)
+    }
+
+    /// Returns the text of the comment if the whole line is a comment.
+    pub(crate) fn comment_text<'a>(&self, line: &'a str) -> Option<&'a str> {
+        let trimmed = line.trim();
+        for prefix in self.line {
+            if let Some(text) = trimmed.strip_prefix(prefix) {
+                // Word prefixes like `REM` must be followed by a space.
+                let is_word_prefix = prefix.ends_with(|c: char| c.is_alphabetic());
+                if !is_word_prefix || text.is_empty() || text.starts_with(char::is_whitespace) {
+                    return Some(text);
+                }
+            }
+        }
+        self.block.iter().find_map(|(open, close)| {
+            trimmed
+                .strip_prefix(open)
+                .and_then(|text| text.strip_suffix(close))
+        })
+    }
+
+    /// Tells whether the whole line is a comment.
+    pub(crate) fn is_comment(&self, line: &str) -> bool {
+        self.comment_text(line).is_some()
+    }
+
+    /// Tells whether the line stays with the item below it, as a comment or
+    /// eg. a Rust attribute.
+    pub(crate) fn is_attached(&self, line: &str) -> bool {
+        let trimmed = line.trim_start();
+        self.is_comment(line)
+            || self
+                .attached
+                .iter()
+                .any(|prefix| trimmed.starts_with(prefix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_comment_text() {
+        for (path, line, text) in [
+            ("a.txt", "  # Keep sorted\n", Some(" Keep sorted")),
+            ("a.txt", "// Keep sorted\n", Some(" Keep sorted")),
+            ("a.sql", "  -- Keep sorted\n", Some(" Keep sorted")),
+            ("a.sql", "/* Keep sorted */\n", Some(" Keep sorted ")),
+            ("a.sql", "# Keep sorted\n", None),
+            ("a.lisp", ";; Keep sorted\n", Some(" Keep sorted")),
+            ("a.ini", "; Keep sorted\n", Some(" Keep sorted")),
+            ("a.tex", "% Keep sorted\n", Some(" Keep sorted")),
+            ("a.md", "<!-- Keep sorted -->\n", Some(" Keep sorted ")),
+            ("a.md", "# Title\n", None),
+            ("a.css", "/* Keep sorted */\n", Some(" Keep sorted ")),
+            ("a.css", "/* Keep sorted\n", None),
+            ("a.bat", "REM Keep sorted\n", Some(" Keep sorted")),
+            ("a.bat", "REM\n", Some("")),
+            ("a.bat", "REMOVE\n", None),
+            ("a.rs", "#[derive(Debug)]\n", None),
+            (".gitignore", "# Keep sorted\n", Some(" Keep sorted")),
+            (".gitignore", "//generated\n", None),
+            ("docs/CODEOWNERS", "//docs @team\n", None),
+            ("BUILD", "# Keep sorted\n", Some(" Keep sorted")),
+        ] {
+            assert_eq!(
+                CommentSyntax::for_path(Path::new(path)).comment_text(line),
+                text,
+                "Unexpected comment text of {:?} in {}",
+                line,
+                path
+            );
+        }
+    }
+}
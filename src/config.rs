@@ -0,0 +1,160 @@
+use globset::{Glob, GlobMatcher, GlobSet, GlobSetBuilder};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::options::SortOptions;
+use crate::{Features, Strategy};
+
+pub const CONFIG_FILE_NAME: &str = "keepsorted.toml";
+
+/// Project configuration from a `keepsorted.toml` file.
+///
+/// Globs are matched against both the path relative to the directory of
+/// the configuration file and the file name.
+#[derive(Debug)]
+pub struct Config {
+    root: PathBuf,
+    features: Features,
+    exclude: GlobSet,
+    strategies: Vec<(GlobMatcher, Strategy)>,
+    sort_options: SortOptions,
+}
+
+#[derive(Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    /// Feature flags enabled by default, eg. `["gitignore"]`.
+    features: Vec<String>,
+    /// Globs of the files and directories to skip when walking directories.
+    exclude: Vec<String>,
+    /// Strategies forced for the files matching the globs, eg. `"*.bzl.tmpl" = "bazel"`.
+    /// The spans of the values keep the order of the file.
+    strategies: BTreeMap<String, toml::Spanned<String>>,
+    /// Default options of every sorted block, eg. `case = "insensitive"`.
+    sort: BTreeMap<String, toml::Value>,
+}
+
+impl Config {
+    /// Finds the closest `keepsorted.toml` in the directory of the file or
+    /// any of its parents and loads it. The file does not need to exist.
+    pub fn discover(path: &Path) -> io::Result<Option<Config>> {
+        let path = absolute_path(path)?;
+        for dir in path.ancestors().skip(1) {
+            let config_path = dir.join(CONFIG_FILE_NAME);
+            if config_path.is_file() {
+                return Config::load(&config_path).map(Some);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Loads the configuration file at `path`.
+    pub fn load(path: &Path) -> io::Result<Config> {
+        let invalid_data = |e: String| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("invalid config {}: {}", path.display(), e),
+            )
+        };
+
+        let content = fs::read_to_string(path)?;
+        let file: ConfigFile = toml::from_str(&content).map_err(|e| invalid_data(e.to_string()))?;
+
+        let features = Features::parse(&file.features).map_err(invalid_data)?;
+
+        let mut exclude = GlobSetBuilder::new();
+        for glob in &file.exclude {
+            exclude.add(Glob::new(glob).map_err(|e| invalid_data(e.to_string()))?);
+        }
+        let exclude = exclude.build().map_err(|e| invalid_data(e.to_string()))?;
+
+        // The first glob in the file that matches picks the strategy.
+        let mut globs: Vec<_> = file.strategies.iter().collect();
+        globs.sort_by_key(|(_, strategy)| strategy.span().start);
+        let mut strategies = Vec::new();
+        for (glob, strategy) in globs {
+            let matcher = Glob::new(glob)
+                .map_err(|e| invalid_data(e.to_string()))?
+                .compile_matcher();
+            strategies.push((matcher, strategy.get_ref().parse().map_err(invalid_data)?));
+        }
+
+        let mut sort_options = SortOptions::default();
+        for (key, value) in &file.sort {
+            let value = match value {
+                toml::Value::String(value) => value.clone(),
+                toml::Value::Integer(value) => value.to_string(),
+                toml::Value::Boolean(value) => (if *value { "yes" } else { "no" }).to_string(),
+                _ => {
+                    return Err(invalid_data(format!(
+                        "invalid value for sort option '{}'",
+                        key
+                    )))
+                }
+            };
+            if !sort_options.set(key, &value).map_err(invalid_data)? {
+                return Err(invalid_data(format!("unknown sort option '{}'", key)));
+            }
+        }
+
+        let root = absolute_path(path)?
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_default();
+        Ok(Config {
+            root,
+            features,
+            exclude,
+            strategies,
+            sort_options,
+        })
+    }
+
+    /// Feature flags enabled by the configuration.
+    pub fn features(&self) -> &Features {
+        &self.features
+    }
+
+    /// Default options of every sorted block.
+    pub fn sort_options(&self) -> &SortOptions {
+        &self.sort_options
+    }
+
+    /// Whether the file or directory should be skipped when walking directories.
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        let path = self.relative_path(path);
+        self.exclude.is_match(&path)
+            || path
+                .ancestors()
+                .filter_map(Path::file_name)
+                .any(|name| self.exclude.is_match(name))
+    }
+
+    /// The strategy forced for the file, if any.
+    pub fn strategy(&self, path: &Path) -> Option<Strategy> {
+        let path = self.relative_path(path);
+        self.strategies
+            .iter()
+            .find(|(matcher, _)| {
+                matcher.is_match(&path)
+                    || path.file_name().is_some_and(|name| matcher.is_match(name))
+            })
+            .map(|(_, strategy)| *strategy)
+    }
+
+    fn relative_path(&self, path: &Path) -> PathBuf {
+        let path = absolute_path(path).unwrap_or_else(|_| path.to_path_buf());
+        path.strip_prefix(&self.root)
+            .map(Path::to_path_buf)
+            .unwrap_or(path)
+    }
+}
+
+/// Makes the path absolute without resolving symlinks, and drops `.` components.
+fn absolute_path(path: &Path) -> io::Result<PathBuf> {
+    Ok(env::current_dir()?.join(path).components().collect())
+}
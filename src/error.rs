@@ -0,0 +1,77 @@
+use std::fmt;
+use std::io;
+
+use crate::features::Feature;
+
+/// Error of the library API.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Invalid option on a directive.
+    InvalidOption {
+        /// 1-based number of the line of the directive.
+        line: usize,
+        message: String,
+    },
+    /// Block that cannot be found as its directive describes it, eg. without
+    /// its end directive.
+    InvalidBlock {
+        /// 1-based number of the line of the directive.
+        line: usize,
+        message: String,
+    },
+    /// File that is not UTF-8 text, eg. a binary file.
+    NotText,
+    /// Feature flags that cannot be enabled together.
+    ConflictingFeatures(Feature, Feature),
+    /// Failure to read or write a file.
+    Io(io::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::InvalidOption { line, message } | Error::InvalidBlock { line, message } => {
+                write!(f, "line {}: {}", line, message)
+            }
+            Error::NotText => write!(f, "not a UTF-8 text file"),
+            Error::ConflictingFeatures(a, b) => {
+                write!(f, "features '{}' and '{}' cannot be enabled together", a, b)
+            }
+            Error::Io(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InvalidOption { .. }
+            | Error::InvalidBlock { .. }
+            | Error::NotText
+            | Error::ConflictingFeatures(..) => None,
+            Error::Io(e) => Some(e),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+/// Errors of the functions returning `io::Result`: invalid options and
+/// blocks are reported as invalid input, and files that are not text as
+/// invalid data.
+impl From<Error> for io::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Io(e) => e,
+            Error::NotText => io::Error::new(io::ErrorKind::InvalidData, e),
+            e => io::Error::new(io::ErrorKind::InvalidInput, e),
+        }
+    }
+}
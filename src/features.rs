@@ -0,0 +1,98 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::{Error, Result};
+
+/// An experimental feature flag, enabling a strategy for some files.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Feature {
+    Gitignore,
+    Codeowners,
+    RustDeriveAlphabetical,
+    RustDeriveCanonical,
+}
+
+/// Features that cannot be enabled together.
+const CONFLICTS: &[(Feature, Feature)] = &[(
+    Feature::RustDeriveAlphabetical,
+    Feature::RustDeriveCanonical,
+)];
+
+impl Feature {
+    pub const ALL: [Feature; 4] = [
+        Feature::Gitignore,
+        Feature::Codeowners,
+        Feature::RustDeriveAlphabetical,
+        Feature::RustDeriveCanonical,
+    ];
+
+    /// Name of the feature as used in `--features` and the configuration file.
+    pub fn name(self) -> &'static str {
+        match self {
+            Feature::Gitignore => "gitignore",
+            Feature::Codeowners => "codeowners",
+            Feature::RustDeriveAlphabetical => "rust_derive_alphabetical",
+            Feature::RustDeriveCanonical => "rust_derive_canonical",
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Feature {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Feature::ALL
+            .into_iter()
+            .find(|feature| feature.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = Feature::ALL.iter().map(|feature| feature.name()).collect();
+                format!(
+                    "unknown feature '{}', expected one of: {}",
+                    s,
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// A set of enabled features without conflicting ones.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Features(BTreeSet<Feature>);
+
+impl Features {
+    /// Returns `Error::ConflictingFeatures` if two of the features cannot be
+    /// enabled together.
+    pub fn new(features: impl IntoIterator<Item = Feature>) -> Result<Self> {
+        let features: BTreeSet<Feature> = features.into_iter().collect();
+        for &(a, b) in CONFLICTS {
+            if features.contains(&a) && features.contains(&b) {
+                return Err(Error::ConflictingFeatures(a, b));
+            }
+        }
+        Ok(Self(features))
+    }
+
+    /// Parses the names of the features, eg. from the configuration file.
+    pub fn parse<S: AsRef<str>>(names: &[S]) -> Result<Self, String> {
+        let features = names
+            .iter()
+            .map(|name| name.as_ref().parse())
+            .collect::<Result<Vec<Feature>, String>>()?;
+        Features::new(features).map_err(|e| e.to_string())
+    }
+
+    pub fn is_enabled(&self, feature: Feature) -> bool {
+        self.0.contains(&feature)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = Feature> + '_ {
+        self.0.iter().copied()
+    }
+}
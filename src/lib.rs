@@ -1,130 +1,551 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::fs::{self, File};
-use std::io::{self, BufWriter, Write};
+use similar::TextDiff;
+use std::any::Any;
+use std::collections::{HashMap, VecDeque};
+use std::ffi::OsStr;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::ops::Range;
 use std::path::Path;
+use std::str::FromStr;
+use tempfile::NamedTempFile;
 
+use crate::comments::CommentSyntax;
+use crate::options::SortOptions;
+
+pub mod comments;
+pub mod config;
+pub mod error;
+pub mod features;
+pub mod options;
 pub mod strategies;
 
+pub use crate::error::{Error, Result};
+pub use crate::features::{Feature, Features};
+pub use crate::strategies::{Registry, SortStrategy};
+
 static RE_KEEP_SORTED: Lazy<Regex> = Lazy::new(re_keyword_keep_sorted);
+static RE_KEEP_SORTED_END: Lazy<Regex> = Lazy::new(re_keyword_keep_sorted_end);
 static RE_IGNORE_FILE: Lazy<Regex> = Lazy::new(re_keyword_ignore_file);
 static RE_IGNORE_BLOCK: Lazy<Regex> = Lazy::new(re_keyword_ignore_block);
+static RE_KEEPSORTED_OFF: Lazy<Regex> = Lazy::new(re_keyword_keepsorted_off);
+static RE_KEEPSORTED_ON: Lazy<Regex> = Lazy::new(re_keyword_keepsorted_on);
 
-pub fn process_file(path: &Path, features: Vec<String>) -> io::Result<()> {
-    let mut content = fs::read_to_string(path)?;
-    let ends_with_newline = content.ends_with('\n');
-    if !ends_with_newline {
-        // Ensure content ends with a newline to support line reordering.
-        content.push('\n');
-    }
-
-    let lines: Vec<_> = content.split_inclusive('\n').map(String::from).collect();
-    let output_lines = process_lines(classify(path, features), lines)?;
-
-    let mut writer = BufWriter::new(File::create(path)?);
-    for (i, line) in output_lines.iter().enumerate() {
-        write!(
-            writer,
-            "{}",
-            if i + 1 == output_lines.len() && !ends_with_newline {
-                // Remove the newline if it wasn’t in the original.
-                line.trim_end_matches('\n')
-            } else {
-                line
-            }
-        )?;
+/// Sorts the file in place and returns whether its content changed.
+/// The file is only written if its content changed.
+/// The options are the defaults for every block, eg. from `keepsorted.toml`.
+pub fn process_file(path: &Path, strategy: Strategy, options: &SortOptions) -> Result<bool> {
+    let content = read_text(path)?;
+    let output = process_text(&content, strategy, options)?;
+    if output == content {
+        // Leave the file untouched, eg. to not trigger rebuilds.
+        return Ok(false);
     }
 
-    writer.flush()
+    write_atomically(path, &output)?;
+    Ok(true)
 }
 
-#[derive(Copy, Clone)]
-pub enum Strategy {
-    Generic,
-    Bazel,
-    CargoToml,
-    Gitignore,
-    RustDeriveAlphabetical,
-    RustDeriveCanonical,
+/// Reads the file, which must be UTF-8 text.
+fn read_text(path: &Path) -> Result<String> {
+    String::from_utf8(fs::read(path)?).map_err(|_| Error::NotText)
 }
 
-pub fn process_lines(strategy: Strategy, lines: Vec<String>) -> io::Result<Vec<String>> {
-    if is_ignore_file(&lines) {
-        return Ok(lines);
+/// Writes the content to a temporary file next to the file and renames it over
+/// the file, so that it is never left half-written. Keeps the permissions of
+/// the file, and writes to the target of a symlink rather than replacing it.
+fn write_atomically(path: &Path, content: &str) -> io::Result<()> {
+    let path = if fs::symlink_metadata(path)?.file_type().is_symlink() {
+        fs::canonicalize(path)?
+    } else {
+        path.to_path_buf()
+    };
+    let permissions = fs::metadata(&path)?.permissions();
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+
+    let mut file = NamedTempFile::new_in(dir)?;
+    file.write_all(content.as_bytes())?;
+    file.as_file().sync_all()?;
+    fs::set_permissions(file.path(), permissions)?;
+    file.persist(&path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// Returns the blocks in the file that are not sorted, without modifying the file.
+pub fn check_file(
+    path: &Path,
+    strategy: Strategy,
+    options: &SortOptions,
+) -> Result<Vec<ChangedBlock>> {
+    let content = read_text(path)?;
+    check_text(&content, strategy, options)
+}
+
+/// Returns a unified diff between the file and its sorted version, without
+/// modifying the file. The diff is empty if the file is already sorted.
+pub fn diff_file(path: &Path, strategy: Strategy, options: &SortOptions) -> Result<String> {
+    let content = read_text(path)?;
+    diff_text(path, &content, strategy, options)
+}
+
+/// Same as `process_file`, but for the content in memory, eg. read from stdin.
+pub fn process_text(content: &str, strategy: Strategy, options: &SortOptions) -> Result<String> {
+    let (lines, format) = split_lines(content);
+    let output = process_lines_with_options(strategy, lines, options)?;
+    Ok(join_lines(output, format))
+}
+
+/// Same as `check_file`, but for the content in memory, eg. read from stdin.
+pub fn check_text(
+    content: &str,
+    strategy: Strategy,
+    options: &SortOptions,
+) -> Result<Vec<ChangedBlock>> {
+    let (lines, _) = split_lines(content);
+    check_lines(strategy, lines, options)
+}
+
+/// Same as `diff_file`, but for the content in memory, eg. read from stdin.
+/// The path is only used in the diff header and does not need to exist.
+pub fn diff_text(
+    path: &Path,
+    content: &str,
+    strategy: Strategy,
+    options: &SortOptions,
+) -> Result<String> {
+    let output = process_text(content, strategy, options)?;
+    if output == content {
+        return Ok(String::new());
     }
-    match strategy {
-        Strategy::Generic => crate::strategies::generic::process(lines),
-        Strategy::Bazel => crate::strategies::bazel::process(lines),
-        Strategy::CargoToml => crate::strategies::cargo_toml::process(lines),
-        Strategy::Gitignore => crate::strategies::gitignore::process(lines),
-        Strategy::RustDeriveAlphabetical => {
-            crate::strategies::rust_derive::process(lines, strategy)
+
+    let name = path.display().to_string();
+    Ok(TextDiff::from_lines(content, &output)
+        .unified_diff()
+        .header(&name, &name)
+        .to_string())
+}
+
+/// Options of `sort_str`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Options {
+    pub strategy: Strategy,
+    /// Default options of every sorted block, also set on the directives.
+    pub sort: SortOptions,
+}
+
+impl Options {
+    pub fn new(strategy: Strategy) -> Self {
+        Self {
+            strategy,
+            sort: SortOptions::default(),
         }
-        Strategy::RustDeriveCanonical => crate::strategies::rust_derive::process(lines, strategy),
     }
+
+    /// Picks the strategy and the comment syntax for the file based on its path
+    /// and the enabled feature flags. The file does not need to exist.
+    pub fn for_path(path: &Path, features: &Features) -> Self {
+        Self {
+            strategy: classify(path, features),
+            sort: SortOptions {
+                comments: CommentSyntax::for_path(path),
+                ..SortOptions::default()
+            },
+        }
+    }
+}
+
+/// Result of `sort_str`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Outcome {
+    /// The sorted content.
+    pub text: String,
+    /// The blocks modified by sorting, in order.
+    pub changes: Vec<ChangedBlock>,
 }
 
-fn classify(path: &Path, features: Vec<String>) -> Strategy {
-    if is_bazel(path) {
-        return Strategy::Bazel;
+impl Outcome {
+    pub fn is_changed(&self) -> bool {
+        !self.changes.is_empty()
     }
-    if is_cargo_toml(path) {
-        return Strategy::CargoToml;
+}
+
+/// A block modified by sorting.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct ChangedBlock {
+    /// 1-based number of the first input line of the block.
+    pub start_line: usize,
+    /// 1-based number of the last input line of the block.
+    pub end_line: usize,
+    pub strategy: Strategy,
+    /// Whether duplicate items were removed from the block by the `unique` option.
+    pub has_duplicates: bool,
+}
+
+/// Sorts the content and reports the modified blocks.
+pub fn sort_str(content: &str, options: &Options) -> Result<Outcome> {
+    let (lines, format) = split_lines(content);
+    let mut changes = Vec::new();
+    let output = process_lines_with_changes(options.strategy, lines, &options.sort, &mut changes)?;
+    Ok(Outcome {
+        text: join_lines(output, format),
+        changes,
+    })
+}
+
+/// Conventions of the original content that are restored on the output.
+struct TextFormat {
+    ends_with_newline: bool,
+    /// Lines end with `\r\n`, as detected on the first line. Used for the lines
+    /// that are not in the original content, eg. reformatted by a strategy.
+    is_crlf: bool,
+    /// Whether each line of the original content ends with `\r\n`, by line in
+    /// the order of the content, so that lines keep their ending when moved.
+    /// Empty if no line ends with `\r\n`.
+    crlf_by_line: HashMap<String, VecDeque<bool>>,
+    /// The content starts with a UTF-8 byte order mark.
+    has_bom: bool,
+}
+
+/// Splits the content into lines that all end with `\n`, without the byte order mark,
+/// and tells the conventions of the original content.
+fn split_lines(content: &str) -> (Vec<String>, TextFormat) {
+    let (content, has_bom) = match content.strip_prefix('\u{feff}') {
+        Some(content) => (content, true),
+        None => (content, false),
+    };
+    let mut format = TextFormat {
+        ends_with_newline: content.ends_with('\n'),
+        is_crlf: content
+            .find('\n')
+            .is_some_and(|i| content[..i].ends_with('\r')),
+        crlf_by_line: HashMap::new(),
+        has_bom,
+    };
+    let has_crlf = content.contains("\r\n");
+    let mut lines = Vec::new();
+    for line in content.split_inclusive('\n') {
+        let (line, is_crlf) = match line.strip_suffix("\r\n") {
+            Some(line) => (format!("{}\n", line), true),
+            None if line.ends_with('\n') => (line.to_string(), false),
+            // Ensure content ends with a newline to support line reordering.
+            None => (format!("{}\n", line), format.is_crlf),
+        };
+        if has_crlf {
+            format
+                .crlf_by_line
+                .entry(line.clone())
+                .or_default()
+                .push_back(is_crlf);
+        }
+        lines.push(line);
+    }
+    if lines.is_empty() {
+        lines.push("\n".to_string());
+    }
+    (lines, format)
+}
+
+/// Joins the lines split by `split_lines` back with the original conventions.
+fn join_lines(lines: Vec<String>, mut format: TextFormat) -> String {
+    let mut output = String::with_capacity(lines.iter().map(String::len).sum());
+    if format.has_bom {
+        output.push('\u{feff}');
+    }
+    for line in lines {
+        let is_crlf = format
+            .crlf_by_line
+            .get_mut(&line)
+            .and_then(VecDeque::pop_front)
+            .unwrap_or(format.is_crlf);
+        match line.strip_suffix('\n') {
+            Some(line) if is_crlf => {
+                output.push_str(line);
+                output.push_str("\r\n");
+            }
+            _ => output.push_str(&line),
+        }
+    }
+    if !format.ends_with_newline {
+        // Remove the newline if it wasn’t in the original.
+        if output.ends_with("\r\n") {
+            output.truncate(output.len() - 2);
+        } else if output.ends_with('\n') {
+            output.pop();
+        }
+    }
+    output
+}
+
+/// A strategy to sort a file, built in or implemented outside of this crate
+/// with `SortStrategy`.
+#[derive(Copy, Clone)]
+pub struct Strategy(&'static dyn SortStrategy);
+
+impl Strategy {
+    pub const GENERIC: Strategy = Strategy(&strategies::generic::Generic);
+    pub const BAZEL: Strategy = Strategy(&strategies::bazel::Bazel);
+    pub const CARGO_TOML: Strategy = Strategy(&strategies::cargo_toml::CargoToml);
+    pub const GITIGNORE: Strategy = Strategy(&strategies::gitignore::Gitignore);
+    pub const RUST_DERIVE_ALPHABETICAL: Strategy = Strategy(&strategies::rust_derive::ALPHABETICAL);
+    pub const RUST_DERIVE_CANONICAL: Strategy = Strategy(&strategies::rust_derive::CANONICAL);
+
+    /// The built-in strategies, in the order they are picked by file.
+    pub const ALL: [Strategy; 6] = [
+        Strategy::GENERIC,
+        Strategy::BAZEL,
+        Strategy::CARGO_TOML,
+        Strategy::GITIGNORE,
+        Strategy::RUST_DERIVE_ALPHABETICAL,
+        Strategy::RUST_DERIVE_CANONICAL,
+    ];
+
+    /// Wraps a strategy implemented outside of this crate, see also `Registry::register`.
+    pub const fn new(strategy: &'static dyn SortStrategy) -> Self {
+        Strategy(strategy)
     }
-    if features.contains(&"gitignore".to_string()) && is_gitignore(path) {
-        return Strategy::Gitignore;
+
+    pub fn implementation(self) -> &'static dyn SortStrategy {
+        self.0
     }
-    if features.contains(&"codeowners".to_string()) && is_codeowners(path) {
-        return Strategy::Gitignore;
+
+    /// Name of the strategy as used in the configuration file.
+    pub fn name(self) -> &'static str {
+        self.0.name()
     }
-    if is_rust(path) {
-        match (
-            features.contains(&"rust_derive_alphabetical".to_string()),
-            features.contains(&"rust_derive_canonical".to_string()),
-        ) {
-            (true, true) => panic!("Mutually exclusive rust_derive feature flags are not allowed"),
-            (true, false) => return Strategy::RustDeriveAlphabetical,
-            (false, true) => return Strategy::RustDeriveCanonical,
+
+    /// Short description of what the strategy sorts.
+    pub fn description(self) -> &'static str {
+        self.0.description()
+    }
+
+    /// Files the strategy is picked for by default.
+    pub fn default_files(self) -> String {
+        if self == Strategy::GENERIC {
+            return "any other file".to_string();
+        }
+        self.0
+            .file_patterns()
+            .iter()
+            .map(|(pattern, feature)| match feature {
+                Some(feature) => format!("{} with the `{}` feature", pattern, feature),
+                None => pattern.to_string(),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl fmt::Debug for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Strategy").field(&self.name()).finish()
+    }
+}
+
+/// Strategies are the same if they have the same implementation type and name,
+/// eg. a custom strategy named `generic` differs from `Strategy::GENERIC`.
+impl PartialEq for Strategy {
+    fn eq(&self, other: &Self) -> bool {
+        Any::type_id(self.0) == Any::type_id(other.0) && self.name() == other.name()
+    }
+}
+
+impl Eq for Strategy {}
+
+impl FromStr for Strategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Strategy::ALL
+            .into_iter()
+            .find(|strategy| strategy.name() == s)
+            .ok_or_else(|| format!("unknown strategy '{}'", s))
+    }
+}
+
+/// Errors are reported as `io::Error`, use `process_lines_with_options` for
+/// the typed `Error`.
+pub fn process_lines(strategy: Strategy, lines: Vec<String>) -> io::Result<Vec<String>> {
+    Ok(process_lines_with_options(
+        strategy,
+        lines,
+        &SortOptions::default(),
+    )?)
+}
+
+/// Same as `process_lines`, but with the default options for every block.
+pub fn process_lines_with_options(
+    strategy: Strategy,
+    lines: Vec<String>,
+    options: &SortOptions,
+) -> Result<Vec<String>> {
+    let mut changes = Vec::new();
+    process_lines_with_changes(strategy, lines, options, &mut changes)
+}
+
+/// Returns the blocks that would be modified by `process_lines_with_options`.
+pub fn check_lines(
+    strategy: Strategy,
+    lines: Vec<String>,
+    options: &SortOptions,
+) -> Result<Vec<ChangedBlock>> {
+    let mut changes = Vec::new();
+    process_lines_with_changes(strategy, lines, options, &mut changes)?;
+    Ok(changes)
+}
+
+/// A block that is modified by sorting, as recorded by the strategies.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Change {
+    /// 0-based range of the input lines of the block.
+    pub(crate) lines: Range<usize>,
+    /// Whether duplicate items were removed from the block by the `unique` option.
+    pub(crate) has_duplicates: bool,
+}
+
+fn process_lines_with_changes(
+    strategy: Strategy,
+    lines: Vec<String>,
+    options: &SortOptions,
+    changed_blocks: &mut Vec<ChangedBlock>,
+) -> Result<Vec<String>> {
+    if is_ignore_file(&lines, &options.comments) {
+        return Ok(lines);
+    }
+
+    let off_regions = off_regions(&lines, &options.comments);
+    let mut changes = Vec::new();
+    let output_lines = crate::strategies::process(
+        strategy.implementation(),
+        lines,
+        options,
+        &off_regions,
+        &mut changes,
+    )?;
+    changed_blocks.extend(changes.into_iter().map(|change| ChangedBlock {
+        start_line: change.lines.start + 1,
+        end_line: change.lines.end,
+        strategy,
+        has_duplicates: change.has_duplicates,
+    }));
+    Ok(output_lines)
+}
+
+/// Finds the 0-based ranges of the input lines from `keepsorted: off` to
+/// `keepsorted: on`, or to the end of the file, which are left as they are.
+fn off_regions(lines: &[String], comments: &CommentSyntax) -> Vec<Range<usize>> {
+    let mut regions = Vec::new();
+    let mut off_start = None;
+    for (i, line) in lines.iter().enumerate() {
+        match off_start {
+            Some(start) if is_directive(&RE_KEEPSORTED_ON, line, comments) => {
+                regions.push(start..i + 1);
+                off_start = None;
+            }
+            None if is_directive(&RE_KEEPSORTED_OFF, line, comments) => off_start = Some(i),
             _ => {}
         }
     }
-    Strategy::Generic
+    if let Some(start) = off_start {
+        regions.push(start..lines.len());
+    }
+    regions
+}
+
+/// Sorts a block that ends right before the input line `end` and records
+/// its input line range in `changes` if sorting modified it.
+pub(crate) fn sort_block<F>(
+    block: Vec<String>,
+    end: usize,
+    options: &SortOptions,
+    changes: &mut Vec<Change>,
+    sort: F,
+) -> Vec<String>
+where
+    F: FnOnce(Vec<String>) -> Vec<String>,
+{
+    let start = end - block.len();
+    let sorted = sort(block.clone());
+    if sorted != block {
+        changes.push(Change {
+            lines: start..end,
+            // Sorting keeps every line, only merging duplicates removes some.
+            has_duplicates: options.unique && sorted.len() < block.len(),
+        });
+    }
+    sorted
 }
 
-fn is_ignore_file(lines: &[String]) -> bool {
-    lines.iter().any(|x| RE_IGNORE_FILE.is_match(x))
+/// Part of a path matched by the file patterns of a strategy.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FilePattern {
+    /// The whole file name, eg. `BUILD`.
+    Name(&'static str),
+    /// The extension of the file name, eg. `bzl` for `defs.bzl`.
+    Extension(&'static str),
 }
 
-fn is_ignore_block(lines: &[String]) -> bool {
-    lines.iter().any(|x| RE_IGNORE_BLOCK.is_match(x))
+impl FilePattern {
+    pub fn matches(self, path: &Path) -> bool {
+        match self {
+            FilePattern::Name(name) => path.file_name() == Some(OsStr::new(name)),
+            FilePattern::Extension(ext) => path.extension() == Some(OsStr::new(ext)),
+        }
+    }
 }
 
-fn is_bazel(path: &Path) -> bool {
-    match path.extension().and_then(|s| s.to_str()) {
-        Some(ext) => matches!(ext, "bazel" | "bzl" | "BUILD" | "WORKSPACE"),
-        None => false,
+impl fmt::Display for FilePattern {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FilePattern::Name(name) => write!(f, "{}", name),
+            FilePattern::Extension(ext) => write!(f, "*.{}", ext),
+        }
     }
 }
 
-fn is_cargo_toml(path: &Path) -> bool {
-    path.is_file() && path.file_name() == Some(std::ffi::OsStr::new("Cargo.toml"))
+/// Picks the strategy for the file based on its path and the enabled feature flags.
+/// The file does not need to exist.
+pub fn classify(path: &Path, features: &Features) -> Strategy {
+    Registry::default().detect(path, features)
+}
+
+/// Matches a directive regex against the text of a comment line.
+fn captures_directive<'a>(
+    re: &Regex,
+    line: &'a str,
+    comments: &CommentSyntax,
+) -> Option<regex::Captures<'a>> {
+    comments
+        .comment_text(line)
+        .and_then(|text| re.captures(text))
 }
 
-fn is_gitignore(path: &Path) -> bool {
-    path.is_file() && path.file_name() == Some(std::ffi::OsStr::new(".gitignore"))
+fn is_directive(re: &Regex, line: &str, comments: &CommentSyntax) -> bool {
+    captures_directive(re, line, comments).is_some()
 }
 
-fn is_codeowners(path: &Path) -> bool {
-    path.is_file() && path.file_name() == Some(std::ffi::OsStr::new("CODEOWNERS"))
+fn is_ignore_file(lines: &[String], comments: &CommentSyntax) -> bool {
+    lines
+        .iter()
+        .any(|x| is_directive(&RE_IGNORE_FILE, x, comments))
 }
 
-fn is_rust(path: &Path) -> bool {
-    path.is_file() && path.extension() == Some(std::ffi::OsStr::new("rs"))
+fn is_ignore_block(lines: &[String], comments: &CommentSyntax) -> bool {
+    lines
+        .iter()
+        .any(|x| is_directive(&RE_IGNORE_BLOCK, x, comments))
 }
 
 fn re_keyword_keep_sorted() -> Regex {
     Regex::new(
-        r"(?i)^\s*(#|\/\/|#\s+keepsorted\s*:|\/\/\s+keepsorted\s*:)\s*keep\s+sorted\s*\.?\s*$",
+        r"(?i)^\s*((keepsorted\s*:\s*)?keep\s+sorted(\s+(?P<start>start))?\s*\.?|keep-sorted\s+(?P<google>start))(?P<options>(\s+[\w-]+=\S*)*)\s*$",
     )
     .expect("Failed to build regex for keep sorted")
 }
@@ -138,9 +559,38 @@ fn test_re_keyword_keep_sorted() {
         "  #   keepsorted  : keep   sorted  .  ",
         "  //  Keep sorted   .  ",
         "  //   keepsorted  : keep   sorted  .  ",
+        "  # Keep sorted start  ",
+        "  //   keepsorted  : keep   sorted start .  ",
+        "  // keep-sorted start",
+        "  # keep-sorted start numeric=yes case=no skip_lines=1",
+        "  # keepsorted: keep sorted case=insensitive numeric=yes reverse=yes unique=yes",
+        "  // Keep sorted start skip_lines=2",
+    ] {
+        assert!(
+            is_directive(&re, line, &CommentSyntax::default()),
+            "The regex failed to match the valid line: '{}'",
+            line
+        );
+    }
+}
+
+fn re_keyword_keep_sorted_end() -> Regex {
+    Regex::new(r"(?i)^\s*(keepsorted\s*:\s*end|keep\s+sorted\s+end|keep-sorted\s+end)\s*\.?\s*$")
+        .expect("Failed to build regex for keep sorted end")
+}
+
+#[test]
+fn test_re_keyword_keep_sorted_end() {
+    let re = re_keyword_keep_sorted_end();
+    for line in [
+        "  #   keepsorted  : end  .  ",
+        "  //   keepsorted  : end  ",
+        "  # Keep sorted end",
+        "  //  keep   sorted   end .  ",
+        "  // keep-sorted end",
     ] {
         assert!(
-            re.is_match(line),
+            is_directive(&re, line, &CommentSyntax::default()),
             "The regex failed to match the valid line: '{}'",
             line
         );
@@ -148,7 +598,7 @@ fn test_re_keyword_keep_sorted() {
 }
 
 fn re_keyword_ignore_file() -> Regex {
-    Regex::new(r"(?i)^\s*(#|\/\/)\s*keepsorted\s*:\s*ignore\s+file\s*\.?\s*$")
+    Regex::new(r"(?i)^\s*keepsorted\s*:\s*ignore\s+file\s*\.?\s*$")
         .expect("Failed to build regex for ignore file")
 }
 
@@ -160,7 +610,7 @@ fn test_re_keyword_ignore_file() {
         "  //   keepsorted  : ignore   file  .  ",
     ] {
         assert!(
-            re.is_match(line),
+            is_directive(&re, line, &CommentSyntax::default()),
             "The regex failed to match the valid line: '{}'",
             line
         );
@@ -168,7 +618,7 @@ fn test_re_keyword_ignore_file() {
 }
 
 fn re_keyword_ignore_block() -> Regex {
-    Regex::new(r"(?i)^\s*(#|\/\/)\s*keepsorted\s*:\s*ignore\s+block\s*\.?\s*$")
+    Regex::new(r"(?i)^\s*keepsorted\s*:\s*ignore\s+block\s*\.?\s*$")
         .expect("Failed to build regex for ignore block")
 }
 
@@ -180,7 +630,33 @@ fn test_re_keyword_ignore_block() {
         "  //   keepsorted  : ignore   block  .  ",
     ] {
         assert!(
-            re.is_match(line),
+            is_directive(&re, line, &CommentSyntax::default()),
+            "The regex failed to match the valid line: '{}'",
+            line
+        );
+    }
+}
+
+fn re_keyword_keepsorted_off() -> Regex {
+    Regex::new(r"(?i)^\s*keepsorted\s*:\s*off\s*\.?\s*$")
+        .expect("Failed to build regex for keepsorted off")
+}
+
+fn re_keyword_keepsorted_on() -> Regex {
+    Regex::new(r"(?i)^\s*keepsorted\s*:\s*on\s*\.?\s*$")
+        .expect("Failed to build regex for keepsorted on")
+}
+
+#[test]
+fn test_re_keyword_keepsorted_off_on() {
+    for (re, line) in [
+        (re_keyword_keepsorted_off(), "  #   keepsorted  : off  .  "),
+        (re_keyword_keepsorted_off(), "  //   keepsorted  : off  "),
+        (re_keyword_keepsorted_on(), "  #   keepsorted  : on  .  "),
+        (re_keyword_keepsorted_on(), "  //   keepsorted  : on  "),
+    ] {
+        assert!(
+            is_directive(&re, line, &CommentSyntax::default()),
             "The regex failed to match the valid line: '{}'",
             line
         );
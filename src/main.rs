@@ -1,7 +1,19 @@
-use clap::{arg, command, Parser};
-use keepsorted::process_file;
-use std::io::{self};
-use std::path::Path;
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser};
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use ignore::WalkBuilder;
+use keepsorted::comments::CommentSyntax;
+use keepsorted::config::Config;
+use keepsorted::options::SortOptions;
+use keepsorted::{
+    check_file, check_text, classify, diff_file, diff_text, process_file, process_text,
+    ChangedBlock, Error, Feature, Features, Result, Strategy,
+};
+use std::collections::HashMap;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::{Arc, Mutex, MutexGuard};
 
 fn about() -> String {
     format!(
@@ -22,57 +34,460 @@ struct Args {
         short = 'p',
         long,
         value_name = "PATH",
-        conflicts_with = "positional_path",
-        help = "Path to the file to run on. This option is mutually exclusive with the positional path."
+        help = "Path to a file or directory to run on. Can be repeated and combined with the positional paths."
     )]
-    path: Option<String>,
+    path: Vec<String>,
 
     #[arg(
         value_name = "PATH",
-        required_unless_present = "path",
-        help = "Path to the file to run on. This is required if the -p option is not used."
+        required_unless_present_any = ["path", "stdin", "list_strategies"],
+        help = "Paths to files or directories to run on. Directories are walked recursively. Use `-` to read from stdin. This is required if the -p option is not used."
     )]
-    positional_path: Option<String>,
+    positional_paths: Vec<String>,
+
+    #[arg(
+        long,
+        conflicts_with_all = ["path", "positional_paths"],
+        help = "Read the content from stdin and write the result to stdout. Same as passing `-` as the path."
+    )]
+    stdin: bool,
+
+    #[arg(
+        long,
+        value_name = "PATH",
+        help = "Path used to pick a strategy for the content read from stdin, eg. `BUILD.bazel`. The file does not need to exist."
+    )]
+    stdin_filename: Option<String>,
 
     #[arg(
         short = 'f',
         long,
         value_name = "FEATURE",
         use_value_delimiter = true,
-        help = "Experimental feature flags. Provide a list of features to enable."
+        value_parser = Feature::from_str,
+        help = "Experimental feature flags. Provide a list of features to enable. Overrides the features from `keepsorted.toml`."
+    )]
+    features: Option<Vec<Feature>>,
+
+    #[arg(
+        short = 's',
+        long,
+        value_name = "STRATEGY",
+        value_parser = Strategy::from_str,
+        help = "Strategy to use for every file instead of picking one from the file name. See --list-strategies."
+    )]
+    strategy: Option<Strategy>,
+
+    #[arg(
+        long,
+        exclusive = true,
+        help = "List the available strategies and the files they are picked for by default."
+    )]
+    list_strategies: bool,
+
+    #[arg(
+        long,
+        help = "Report unsorted blocks as `path:line` without modifying the file, and blocks with duplicates removed by the `unique` option as `path:line: duplicate items`. Exits with a non-zero status if any block is not sorted."
+    )]
+    check: bool,
+
+    #[arg(
+        long,
+        conflicts_with = "check",
+        help = "Print a unified diff of the proposed changes without modifying the file. Exits with a non-zero status if the diff is not empty."
+    )]
+    diff: bool,
+
+    #[arg(
+        long,
+        value_name = "GLOB",
+        value_parser = Glob::new,
+        help = "Only process files matching the glob when walking directories. Can be repeated."
     )]
-    features: Option<Vec<String>>,
+    include: Vec<Glob>,
+
+    #[arg(
+        long,
+        value_name = "GLOB",
+        value_parser = Glob::new,
+        help = "Skip files and directories matching the glob when walking directories. Can be repeated."
+    )]
+    exclude: Vec<Glob>,
+}
+
+/// A file to run on.
+struct Input {
+    path: PathBuf,
+    /// Whether the file was found by walking a directory rather than given explicitly.
+    is_discovered: bool,
+}
+
+#[derive(Default)]
+struct Summary {
+    changed: usize,
+    unchanged: usize,
+    skipped: usize,
+    failed: usize,
 }
 
-fn main() -> io::Result<()> {
+fn main() {
     let args = Args::parse();
+    // Conflicting features are reported like the other invalid arguments.
+    let features = args.features.as_ref().map(|features| {
+        Features::new(features.iter().copied())
+            .unwrap_or_else(|e| Args::command().error(ErrorKind::ArgumentConflict, e).exit())
+    });
 
-    // Get the path from either the option or the positional argument
-    let file_path = args
+    if args.list_strategies {
+        list_strategies();
+        return;
+    }
+
+    // Get the paths from both the option and the positional arguments
+    let paths: Vec<PathBuf> = args
         .path
-        .or(args.positional_path)
-        .expect("Path must be provided");
+        .iter()
+        .chain(&args.positional_paths)
+        .map(PathBuf::from)
+        .collect();
+
+    let configs = Arc::new(Configs::default());
 
-    let path = Path::new(&file_path);
+    let stdin_path = Path::new("-");
+    if args.stdin || paths.iter().any(|path| path == stdin_path) {
+        if paths.len() > 1 {
+            eprintln!(
+                "{}: reading from stdin cannot be combined with other paths",
+                env!("CARGO_PKG_NAME")
+            );
+            std::process::exit(2);
+        }
+        match run_stdin(&args, features.as_ref(), &configs) {
+            Ok(is_changed) => {
+                if (args.check || args.diff) && is_changed {
+                    std::process::exit(1);
+                }
+            }
+            Err(e) => {
+                eprintln!("{}: failed to process stdin: {}", env!("CARGO_PKG_NAME"), e);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
 
-    if path.is_dir() {
+    let filter = PathFilter::new(&args.include, &args.exclude).unwrap_or_else(|e| {
+        eprintln!("{}: invalid glob: {}", env!("CARGO_PKG_NAME"), e);
+        std::process::exit(2);
+    });
+
+    let mut summary = Summary::default();
+    let mut inputs = Vec::new();
+    for path in &paths {
+        collect_inputs(path, &filter, &configs, &mut inputs, &mut summary);
+    }
+    let is_verbose = inputs.len() > 1 || paths.iter().any(|path| path.is_dir());
+
+    for input in &inputs {
+        let config = match configs.get(&input.path) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("{}: {}", env!("CARGO_PKG_NAME"), e);
+                summary.failed += 1;
+                continue;
+            }
+        };
+        let strategy = resolve_strategy(&args, features.as_ref(), config.as_deref(), &input.path);
+        let options = resolve_options(config.as_deref(), &input.path);
+        match run(&args, &input.path, strategy, &options) {
+            Ok(true) => {
+                summary.changed += 1;
+                if is_verbose && !args.check && !args.diff {
+                    eprintln!("sorted {}", input.path.display());
+                }
+            }
+            Ok(false) => summary.unchanged += 1,
+            Err(Error::NotText) if input.is_discovered => {
+                // Skip binary files found while walking directories.
+                summary.skipped += 1;
+            }
+            Err(e) => {
+                eprintln!(
+                    "{}: failed to process file {}: {}",
+                    env!("CARGO_PKG_NAME"),
+                    input.path.display(),
+                    e
+                );
+                summary.failed += 1;
+            }
+        }
+    }
+
+    if is_verbose {
+        let changed = if args.check || args.diff {
+            "would be sorted"
+        } else {
+            "sorted"
+        };
         eprintln!(
-            "{}: read {}: is a directory",
+            "{}: {} {}, {} left unchanged, {} skipped, {} failed",
             env!("CARGO_PKG_NAME"),
-            path.display()
+            files(summary.changed),
+            changed,
+            files(summary.unchanged),
+            files(summary.skipped),
+            files(summary.failed),
         );
+    }
+
+    let is_unsorted = (args.check || args.diff) && summary.changed > 0;
+    if summary.failed > 0 || is_unsorted {
         std::process::exit(1);
     }
+}
 
-    // Check for experimental features
-    let features = args.features.unwrap_or_default();
-    process_file(path, features).map_err(|e| {
-        eprintln!(
-            "{}: failed to process file {}: {}",
-            env!("CARGO_PKG_NAME"),
-            path.display(),
-            e
-        );
-        e
-    })
+/// Runs on a single file in the selected mode and returns whether the file
+/// is (or would be) changed.
+fn run(args: &Args, path: &Path, strategy: Strategy, options: &SortOptions) -> Result<bool> {
+    if args.check {
+        let unsorted = check_file(path, strategy, options)?;
+        print_unsorted(path, &unsorted);
+        return Ok(!unsorted.is_empty());
+    }
+
+    if args.diff {
+        let diff = diff_file(path, strategy, options)?;
+        print!("{}", diff);
+        return Ok(!diff.is_empty());
+    }
+
+    process_file(path, strategy, options)
+}
+
+/// Runs on the content read from stdin in the selected mode and returns whether
+/// the content is (or would be) changed. The result is written to stdout.
+fn run_stdin(args: &Args, features: Option<&Features>, configs: &Configs) -> Result<bool> {
+    let mut content = String::new();
+    io::stdin().read_to_string(&mut content)?;
+    let path = PathBuf::from(args.stdin_filename.as_deref().unwrap_or("-"));
+    let config = configs.get(&path)?;
+    let strategy = resolve_strategy(args, features, config.as_deref(), &path);
+    let options = resolve_options(config.as_deref(), &path);
+
+    if args.check {
+        let unsorted = check_text(&content, strategy, &options)?;
+        print_unsorted(&path, &unsorted);
+        return Ok(!unsorted.is_empty());
+    }
+
+    if args.diff {
+        let diff = diff_text(&path, &content, strategy, &options)?;
+        print!("{}", diff);
+        return Ok(!diff.is_empty());
+    }
+
+    let output = process_text(&content, strategy, &options)?;
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(output.as_bytes())?;
+    stdout.flush()?;
+    Ok(output != content)
+}
+
+/// Prints the unsorted blocks as `path:line`, noting the blocks with duplicates.
+fn print_unsorted(path: &Path, unsorted: &[ChangedBlock]) {
+    for block in unsorted {
+        let line = block.start_line;
+        if block.has_duplicates {
+            println!("{}:{}: duplicate items", path.display(), line);
+        } else {
+            println!("{}:{}", path.display(), line);
+        }
+    }
+}
+
+/// Picks the strategy for the file. The strategy and feature flags from the
+/// command line override the ones from the configuration.
+fn resolve_strategy(
+    args: &Args,
+    features: Option<&Features>,
+    config: Option<&Config>,
+    path: &Path,
+) -> Strategy {
+    if let Some(strategy) = args.strategy {
+        return strategy;
+    }
+    if let Some(strategy) = config.and_then(|config| config.strategy(path)) {
+        return strategy;
+    }
+    match (features, config) {
+        (Some(features), _) => classify(path, features),
+        (None, Some(config)) => classify(path, config.features()),
+        (None, None) => classify(path, &Features::default()),
+    }
+}
+
+/// Picks the default options of every sorted block in the file, with the
+/// comment syntax of the file.
+fn resolve_options(config: Option<&Config>, path: &Path) -> SortOptions {
+    let mut options = config
+        .map(|config| config.sort_options().clone())
+        .unwrap_or_default();
+    options.comments = CommentSyntax::for_path(path);
+    options
+}
+
+/// Configurations discovered for the processed files, cached by directory.
+/// Shared with the directory walk to skip the excluded directories.
+#[derive(Default)]
+struct Configs {
+    by_dir: Mutex<HashMap<PathBuf, Option<Arc<Config>>>>,
+}
+
+impl Configs {
+    fn get(&self, path: &Path) -> io::Result<Option<Arc<Config>>> {
+        let dir = path.parent().unwrap_or(Path::new("")).to_path_buf();
+        if let Some(config) = self.by_dir().get(&dir) {
+            return Ok(config.clone());
+        }
+        let config = Config::discover(path)?.map(Arc::new);
+        self.by_dir().insert(dir, config.clone());
+        Ok(config)
+    }
+
+    /// Tells whether the closest configuration excludes the file or directory.
+    /// Invalid configurations are reported when processing the files.
+    fn is_excluded(&self, path: &Path) -> bool {
+        matches!(self.get(path), Ok(Some(config)) if config.is_excluded(path))
+    }
+
+    fn by_dir(&self) -> MutexGuard<'_, HashMap<PathBuf, Option<Arc<Config>>>> {
+        // The cache stays consistent even if a thread panicked while holding it.
+        self.by_dir.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Include and exclude globs applied to the files found by walking directories.
+/// Globs are matched against both the path relative to the walked directory
+/// and the file name.
+struct PathFilter {
+    include: Option<GlobSet>,
+    exclude: GlobSet,
+}
+
+impl PathFilter {
+    fn new(include: &[Glob], exclude: &[Glob]) -> Result<Self, globset::Error> {
+        let include = if include.is_empty() {
+            None
+        } else {
+            Some(build_glob_set(include)?)
+        };
+        let exclude = build_glob_set(exclude)?;
+        Ok(Self { include, exclude })
+    }
+
+    fn is_included(&self, relative_path: &Path) -> bool {
+        match &self.include {
+            Some(set) => is_glob_match(set, relative_path),
+            None => true,
+        }
+    }
+
+    fn is_excluded(&self, relative_path: &Path) -> bool {
+        is_glob_match(&self.exclude, relative_path)
+    }
+}
+
+fn build_glob_set(globs: &[Glob]) -> Result<GlobSet, globset::Error> {
+    let mut builder = GlobSetBuilder::new();
+    for glob in globs {
+        builder.add(glob.clone());
+    }
+    builder.build()
+}
+
+fn is_glob_match(set: &GlobSet, relative_path: &Path) -> bool {
+    set.is_match(relative_path)
+        || relative_path
+            .file_name()
+            .is_some_and(|name| set.is_match(name))
+}
+
+/// Collects the file at `path`, or all files under it if it is a directory.
+///
+/// Directory traversal honors `.gitignore` and `.ignore` files, skips `.git`,
+/// does not follow symlinks and applies the include and exclude globs, and
+/// the exclude globs of the configuration of each directory.
+/// Entries that cannot be read are reported and counted as failed, without
+/// stopping the walk.
+fn collect_inputs(
+    path: &Path,
+    filter: &PathFilter,
+    configs: &Arc<Configs>,
+    inputs: &mut Vec<Input>,
+    summary: &mut Summary,
+) {
+    if !path.is_dir() {
+        inputs.push(Input {
+            path: path.to_path_buf(),
+            is_discovered: false,
+        });
+        return;
+    }
+
+    let walker = WalkBuilder::new(path)
+        .hidden(false)
+        .require_git(false)
+        .sort_by_file_name(|a, b| a.cmp(b))
+        .filter_entry({
+            let root = path.to_path_buf();
+            let exclude = filter.exclude.clone();
+            let configs = Arc::clone(configs);
+            move |entry| {
+                let relative_path = entry.path().strip_prefix(&root).unwrap_or(entry.path());
+                entry.depth() == 0
+                    || (entry.file_name() != ".git"
+                        && !is_glob_match(&exclude, relative_path)
+                        && !configs.is_excluded(entry.path()))
+            }
+        })
+        .build();
+    for entry in walker {
+        let entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                // The error names the entry that cannot be read.
+                eprintln!("{}: {}", env!("CARGO_PKG_NAME"), e);
+                summary.failed += 1;
+                continue;
+            }
+        };
+        if !entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+        {
+            continue;
+        }
+        let relative_path = entry.path().strip_prefix(path).unwrap_or(entry.path());
+        if filter.is_included(relative_path) && !filter.is_excluded(relative_path) {
+            inputs.push(Input {
+                path: entry.into_path(),
+                is_discovered: true,
+            });
+        }
+    }
+}
+
+fn list_strategies() {
+    for strategy in Strategy::ALL {
+        println!("{}", strategy.name());
+        println!("    {}", strategy.description());
+        println!("    Default files: {}", strategy.default_files());
+    }
+}
+
+fn files(count: usize) -> String {
+    match count {
+        1 => "1 file".to_string(),
+        n => format!("{} files", n),
+    }
 }
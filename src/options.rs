@@ -0,0 +1,273 @@
+use crate::comments::CommentSyntax;
+use regex::Regex;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::iter::Peekable;
+use std::str::Chars;
+
+/// Options of Google keep-sorted markers that are accepted but not supported.
+const GOOGLE_OPTIONS: &[&str] = &[
+    "allow_yaml_lists",
+    "block",
+    "group_prefixes",
+    "ignore_prefixes",
+    "lint",
+    "newline_separated",
+    "prefix_order",
+    "sticky_comments",
+    "sticky_prefixes",
+];
+
+/// Options of a sorted block, set on the directive line,
+/// eg. `# keepsorted: keep sorted skip_lines=1`, or as defaults in `keepsorted.toml`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct SortOptions {
+    /// Number of lines at the beginning of the block to keep in place, eg. a table header.
+    pub skip_lines: usize,
+    /// Compare items ignoring case, and break ties by byte order.
+    pub case_insensitive: bool,
+    /// Compare runs of digits by their numeric value, eg. `file2` before `file10`.
+    pub numeric: bool,
+    /// Remove duplicate items after sorting, merging their comments.
+    pub unique: bool,
+    /// Sort items in descending order.
+    pub reverse: bool,
+    /// Sort items by the first capture group of the regex, eg. the name of `NAME=value`.
+    pub by_regex: Option<OptionRegex>,
+    /// Attach lines indented deeper than the first item to the preceding item,
+    /// eg. the continuation lines of a YAML list of mappings.
+    pub group_by_indent: bool,
+    /// Number of groups separated by empty lines covered by a `Keep sorted` directive,
+    /// each sorted independently. A single group if not set.
+    pub groups: Option<usize>,
+    /// Cover every group until a line matching the regex with a `Keep sorted` directive.
+    pub until: Option<OptionRegex>,
+    /// Comment syntax of the file, picked from its extension rather than set as an option.
+    pub comments: CommentSyntax,
+}
+
+/// Regex value of an option, compared by its pattern.
+#[derive(Clone, Debug)]
+pub struct OptionRegex(Regex);
+
+impl OptionRegex {
+    /// The pattern of the regex as written in the option.
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    pub(crate) fn is_match(&self, line: &str) -> bool {
+        self.0.is_match(line.trim_end_matches('\n'))
+    }
+
+    /// Returns the first capture group, or the whole match if the regex has no group.
+    /// Items that do not match are sorted by the whole line.
+    fn key<'a>(&self, item: &'a str) -> &'a str {
+        let item = item.trim_end_matches('\n');
+        match self.0.captures(item) {
+            Some(captures) => captures
+                .get(1)
+                .or_else(|| captures.get(0))
+                .map_or(item, |key| key.as_str()),
+            None => item,
+        }
+    }
+}
+
+impl PartialEq for OptionRegex {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.as_str() == other.0.as_str()
+    }
+}
+
+impl Eq for OptionRegex {}
+
+impl SortOptions {
+    /// Parses whitespace-separated `key=value` options on top of `self`.
+    ///
+    /// The options of Google keep-sorted markers that are not supported are
+    /// ignored, other unknown keys are errors.
+    pub fn parse(&self, options: &str) -> Result<SortOptions, String> {
+        let mut result = self.clone();
+        for option in options.split_whitespace() {
+            let (key, value) = option
+                .split_once('=')
+                .ok_or_else(|| format!("invalid option '{}', expected 'key=value'", option))?;
+            if !result.set(key, value)? && !GOOGLE_OPTIONS.contains(&key) {
+                return Err(format!("unknown option '{}'", key));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Sets a single option and returns whether the key is known.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<bool, String> {
+        match key {
+            "skip_lines" => {
+                self.skip_lines = value
+                    .parse()
+                    .map_err(|_| invalid_value(key, value, "a number"))?;
+            }
+            "case" => {
+                self.case_insensitive = match value {
+                    // Google keep-sorted uses `case=no` for case-insensitive sorting.
+                    "sensitive" | "yes" | "true" => false,
+                    "insensitive" | "no" | "false" => true,
+                    _ => return Err(invalid_value(key, value, "'sensitive' or 'insensitive'")),
+                };
+            }
+            "numeric" => self.numeric = parse_bool(key, value)?,
+            // `remove_duplicates` is the name of the option in Google keep-sorted.
+            "unique" | "remove_duplicates" => self.unique = parse_bool(key, value)?,
+            "reverse" => self.reverse = parse_bool(key, value)?,
+            "group" => {
+                self.group_by_indent = match value {
+                    // Google keep-sorted uses `group=yes` and `group=no`.
+                    "indent" | "yes" | "true" => true,
+                    "line" | "no" | "false" => false,
+                    _ => return Err(invalid_value(key, value, "'indent' or 'line'")),
+                };
+            }
+            "by_regex" => self.by_regex = Some(parse_regex(key, value)?),
+            "groups" => {
+                self.groups = match value.parse() {
+                    Ok(groups) if groups > 0 => Some(groups),
+                    _ => return Err(invalid_value(key, value, "a positive number")),
+                };
+            }
+            "until" => self.until = Some(parse_regex(key, value)?),
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Compares two items of a block.
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        let (key_a, key_b) = (self.key(a), self.key(b));
+        let ordering = if self.numeric {
+            natural_cmp(&key_a, &key_b)
+        } else {
+            key_a.cmp(&key_b)
+        };
+        self.order(ordering.then_with(|| a.cmp(b)))
+    }
+
+    /// Compares two single-line items of a block by their key extracted with
+    /// the `by_regex` option, and breaks ties by the whole line.
+    pub fn compare_items(&self, a: &str, b: &str) -> Ordering {
+        match &self.by_regex {
+            Some(regex) => self
+                .compare(regex.key(a), regex.key(b))
+                .then_with(|| self.compare(a, b)),
+            None => self.compare(a, b),
+        }
+    }
+
+    /// Reverses an ordering of items for descending sorting.
+    pub fn order(&self, ordering: Ordering) -> Ordering {
+        if self.reverse {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
+    fn key<'a>(&self, s: &'a str) -> Cow<'a, str> {
+        if self.case_insensitive {
+            Cow::Owned(s.to_lowercase())
+        } else {
+            Cow::Borrowed(s)
+        }
+    }
+
+    /// Compares two multi-line items of a block line by line.
+    pub fn compare_lines(&self, a: &[String], b: &[String]) -> Ordering {
+        a.iter()
+            .zip(b)
+            .map(|(a, b)| self.compare(a, b))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or_else(|| self.order(a.len().cmp(&b.len())))
+    }
+}
+
+/// Compares strings with runs of ASCII digits compared by their numeric value,
+/// eg. `v1.9` before `v1.10`.
+fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let mut a = a.chars().peekable();
+    let mut b = b.chars().peekable();
+    loop {
+        let ordering = match (a.peek(), b.peek()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+                let x = take_digits(&mut a);
+                let y = take_digits(&mut b);
+                let x = x.trim_start_matches('0');
+                let y = y.trim_start_matches('0');
+                x.len().cmp(&y.len()).then_with(|| x.cmp(y))
+            }
+            (Some(x), Some(y)) => {
+                let ordering = x.cmp(y);
+                a.next();
+                b.next();
+                ordering
+            }
+        };
+        if ordering.is_ne() {
+            return ordering;
+        }
+    }
+}
+
+fn take_digits(chars: &mut Peekable<Chars>) -> String {
+    let mut digits = String::new();
+    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+        digits.push(c);
+    }
+    digits
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, String> {
+    match value {
+        "yes" | "true" => Ok(true),
+        "no" | "false" => Ok(false),
+        _ => Err(invalid_value(key, value, "'yes' or 'no'")),
+    }
+}
+
+fn parse_regex(key: &str, value: &str) -> Result<OptionRegex, String> {
+    Regex::new(value)
+        .map(OptionRegex)
+        .map_err(|_| invalid_value(key, value, "a regex"))
+}
+
+fn invalid_value(key: &str, value: &str, expected: &str) -> String {
+    format!(
+        "invalid value '{}' for option '{}', expected {}",
+        value, key, expected
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_natural_cmp() {
+        let ordered_items = [
+            "", "0", "1", "2", "10", "a", "file", "file1", "file2", "file10", "file10a", "v1.9",
+            "v1.10", "v1.10.1", "v2",
+        ];
+        for window in ordered_items.windows(2) {
+            assert_eq!(
+                natural_cmp(window[0], window[1]),
+                Ordering::Less,
+                "Sort order incorrect: {:?} >= {:?}",
+                window[0],
+                window[1]
+            );
+        }
+    }
+}
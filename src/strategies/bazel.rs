@@ -1,101 +1,114 @@
 use std::cmp::Ordering;
-use std::io;
-
-use crate::{is_ignore_block, re_keyword_keep_sorted};
-
-pub(crate) fn process(lines: Vec<String>) -> io::Result<Vec<String>> {
-    let re = re_keyword_keep_sorted();
-    let mut output_lines = Vec::new();
-    let mut block = Vec::new();
-    let mut is_scope = false;
-    let mut is_sorting_block = false;
-    let mut is_ignore_block_prev_line = false;
-
-    for line in lines {
-        // Trim the input line
-        let trimmed = line.trim();
-
-        // Find and remove the portion of the line starting from the '#' character
-        let line_without_comment = trimmed.split('#').next().unwrap_or("").trim();
-
-        if line_without_comment.contains('[') {
-            is_scope = true;
-            output_lines.push(line);
-        } else if is_scope {
-            if re.is_match(&line) {
-                if let Some(prev_line) = output_lines.last() {
-                    is_ignore_block_prev_line = is_ignore_block(&[prev_line.clone()]);
-                }
-                is_sorting_block = true;
-                output_lines.push(line);
-            } else if is_sorting_block
-                && (line_without_comment.contains(']') || line.trim().is_empty())
-            {
-                block = sort(block, is_ignore_block_prev_line);
-                is_ignore_block_prev_line = false;
-                is_sorting_block = false;
-                output_lines.append(&mut block);
-                output_lines.push(line);
-            } else if is_sorting_block {
-                block.push(line);
-            } else {
-                output_lines.push(line);
-            }
-        } else {
-            output_lines.push(line);
-        }
+
+use crate::options::SortOptions;
+use crate::strategies::{
+    is_end_directive, is_ignore_block_prev_line, parse_directive, push_block, Block, Item,
+    SortStrategy,
+};
+use crate::{Error, Feature, FilePattern, Result};
+
+/// Items of lists after a `# Keep sorted` directive, in the order of `buildifier`.
+pub struct Bazel;
+
+impl SortStrategy for Bazel {
+    fn name(&self) -> &'static str {
+        "bazel"
     }
 
-    if is_sorting_block {
-        block = sort(block, is_ignore_block_prev_line);
-        output_lines.append(&mut block);
+    fn description(&self) -> &'static str {
+        "Items of lists after a `# Keep sorted` directive, in Bazel order"
     }
 
-    Ok(output_lines)
-}
+    fn file_patterns(&self) -> &'static [(FilePattern, Option<Feature>)] {
+        &[
+            (FilePattern::Name("BUILD"), None),
+            (FilePattern::Name("WORKSPACE"), None),
+            (FilePattern::Extension("bazel"), None),
+            (FilePattern::Extension("bzl"), None),
+            (FilePattern::Extension("star"), None),
+            (FilePattern::Extension("BUILD"), None),
+            (FilePattern::Extension("WORKSPACE"), None),
+        ]
+    }
 
-#[derive(Default)]
-struct Item {
-    comment: Vec<String>,
-    code: String,
-    sort_key: BazelSortKey,
-}
+    fn find_blocks(&self, lines: &[String], defaults: &SortOptions) -> Result<Vec<Block>> {
+        let mut blocks = Vec::new();
+        // Start of the block being sorted, after the directive and the skipped lines.
+        let mut block_start = None;
+        let mut is_scope = false;
+        // Explicit blocks end with `keepsorted: end` rather than at the end of the list.
+        let mut is_explicit_block = false;
+        // Index of the line of the last directive.
+        let mut directive_line = 0;
+        let mut is_ignore_block = false;
+        let mut lines_to_skip = 0;
+        let mut options = defaults.clone();
+
+        for (i, line) in lines.iter().enumerate() {
+            // Find and remove the portion of the line starting from the '#' character
+            let line_without_comment = line.trim().split('#').next().unwrap_or("").trim();
+
+            if is_end_directive(line, defaults) {
+                if let Some(start) = block_start.take() {
+                    push_block(&mut blocks, lines, start..i, &options, is_ignore_block);
+                }
+                is_explicit_block = false;
+            } else if is_explicit_block {
+                // The line is part of the block, unless it is skipped.
+                if lines_to_skip > 0 {
+                    lines_to_skip -= 1;
+                    block_start = Some(i + 1);
+                }
+            } else if line_without_comment.contains('[') {
+                if let Some(start) = block_start.take() {
+                    push_block(&mut blocks, lines, start..i, &options, is_ignore_block);
+                }
+                is_scope = true;
+            } else if !is_scope {
+                continue;
+            } else if let Some(directive) = parse_directive(line, i, defaults)? {
+                if let Some(start) = block_start.take() {
+                    push_block(&mut blocks, lines, start..i, &options, is_ignore_block);
+                }
+                is_ignore_block = is_ignore_block_prev_line(lines, i, defaults);
+                block_start = Some(i + 1);
+                directive_line = i;
+                is_explicit_block = directive.is_explicit;
+                options = directive.options;
+                lines_to_skip = options.skip_lines;
+            } else if let Some(start) =
+                block_start.filter(|_| line_without_comment.contains(']') || line.trim().is_empty())
+            {
+                push_block(&mut blocks, lines, start..i, &options, is_ignore_block);
+                block_start = None;
+            } else if block_start.is_some() && lines_to_skip > 0 {
+                lines_to_skip -= 1;
+                block_start = Some(i + 1);
+            }
+        }
 
-/// Sorts a block of lines, keeping associated comments with their items.
-fn sort(block: Vec<String>, is_ignore_block_prev_line: bool) -> Vec<String> {
-    if is_ignore_block_prev_line || is_ignore_block(&block) {
-        return block;
-    }
-    let n = block.len();
-    let mut items = Vec::with_capacity(n);
-    let mut current_item = Item::default();
-    for line in block {
-        if is_single_line_comment(&line) {
-            current_item.comment.push(line);
-        } else {
-            items.push(Item {
-                comment: std::mem::take(&mut current_item.comment),
-                code: line.clone(),
-                sort_key: BazelSortKey::new(&line),
+        if is_explicit_block {
+            return Err(Error::InvalidBlock {
+                line: directive_line + 1,
+                message: "missing end directive for the block started here".to_string(),
             });
         }
-    }
-    let trailing_comments = current_item.comment;
-
-    items.sort_by(|a, b| a.sort_key.cmp(&b.sort_key));
+        if let Some(start) = block_start {
+            push_block(
+                &mut blocks,
+                lines,
+                start..lines.len(),
+                &options,
+                is_ignore_block,
+            );
+        }
 
-    let mut result = Vec::with_capacity(n);
-    for item in items {
-        result.extend(item.comment);
-        result.push(item.code);
+        Ok(blocks)
     }
-    result.extend(trailing_comments);
-
-    result
-}
 
-fn is_single_line_comment(line: &str) -> bool {
-    line.trim().starts_with('#')
+    fn compare_items(&self, a: &Item, b: &Item, options: &SortOptions) -> Ordering {
+        BazelSortKey::new(a.first_line()).compare(&BazelSortKey::new(b.first_line()), options)
+    }
 }
 
 // From: https://sourcegraph.com/github.com/bazelbuild/buildtools@92a716d768c05fa90e241fd2c2b0411125a0ef89/-/blob/build/rewrite.go
@@ -125,19 +138,23 @@ impl BazelSortKey {
         };
 
         let split = line_without_comment
-            .split(|c| c == '.' || c == ':' || c == '"')
+            .split(['.', ':', '"'])
             .map(ToString::to_string)
             .collect();
 
         Self { phase, split }
     }
+
+    pub(crate) fn compare(&self, other: &Self, options: &SortOptions) -> Ordering {
+        options
+            .order(self.phase.cmp(&other.phase))
+            .then_with(|| options.compare_lines(&self.split, &other.split))
+    }
 }
 
 impl Ord for BazelSortKey {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.phase
-            .cmp(&other.phase)
-            .then_with(|| self.split.cmp(&other.split))
+        self.compare(other, &SortOptions::default())
     }
 }
 
@@ -1,44 +1,85 @@
-use std::io;
+use crate::options::SortOptions;
+use crate::strategies::{is_ignore_block_prev_line, push_block, Block, Item, SortStrategy};
+use crate::{Feature, FilePattern, Result};
 
-use crate::is_ignore_block;
+/// Dependencies in the dependency sections of `Cargo.toml`.
+pub struct CargoToml;
 
-pub(crate) fn process(lines: Vec<String>) -> io::Result<Vec<String>> {
-    let mut output_lines: Vec<String> = Vec::new();
-    let mut block = Vec::new();
-    let mut is_sorting_block = false;
-    let mut is_ignore_block_prev_line = false;
+impl SortStrategy for CargoToml {
+    fn name(&self) -> &'static str {
+        "cargo_toml"
+    }
+
+    fn description(&self) -> &'static str {
+        "Dependencies in the dependency sections"
+    }
+
+    fn file_patterns(&self) -> &'static [(FilePattern, Option<Feature>)] {
+        &[(FilePattern::Name("Cargo.toml"), None)]
+    }
+
+    fn find_blocks(&self, lines: &[String], defaults: &SortOptions) -> Result<Vec<Block>> {
+        let mut blocks = Vec::new();
+        // Start of the section being sorted, after its header.
+        let mut block_start = None;
+        let mut is_ignore_block = false;
 
-    for line in lines {
-        let trimmed = line.trim();
-        let line_without_comment = trimmed.split('#').next().unwrap_or("").trim();
+        for (i, line) in lines.iter().enumerate() {
+            let line_without_comment = line.trim().split('#').next().unwrap_or("").trim();
 
-        if is_block_start(&line) {
-            if let Some(prev_line) = output_lines.last() {
-                is_ignore_block_prev_line = is_ignore_block(&[prev_line.clone()]);
+            if is_block_start(line) {
+                if let Some(start) = block_start.take() {
+                    push_block(&mut blocks, lines, start..i, defaults, is_ignore_block);
+                }
+                is_ignore_block = is_ignore_block_prev_line(lines, i, defaults);
+                block_start = Some(i + 1);
+            } else if let Some(start) = block_start
+                .filter(|_| line.trim().is_empty() || line_without_comment.starts_with('['))
+            {
+                push_block(&mut blocks, lines, start..i, defaults, is_ignore_block);
+                block_start = None;
             }
-            is_sorting_block = true;
-            output_lines.push(line);
-        } else if is_sorting_block
-            && (line.trim().is_empty() || line_without_comment.starts_with('['))
-        {
-            block = sort(block, is_ignore_block_prev_line);
-            is_ignore_block_prev_line = false;
-            is_sorting_block = false;
-            output_lines.append(&mut block);
-            output_lines.push(line);
-        } else if is_sorting_block {
-            block.push(line);
-        } else {
-            output_lines.push(line);
         }
-    }
 
-    if is_sorting_block {
-        block = sort(block, is_ignore_block_prev_line);
-        output_lines.append(&mut block);
+        if let Some(start) = block_start {
+            push_block(
+                &mut blocks,
+                lines,
+                start..lines.len(),
+                defaults,
+                is_ignore_block,
+            );
+        }
+
+        Ok(blocks)
     }
 
-    Ok(output_lines)
+    /// Keeps the comments with their dependencies, and the lines of inline
+    /// tables written on several lines together.
+    fn split_items(&self, block: Vec<String>, options: &SortOptions) -> Vec<Item> {
+        let mut items = Vec::with_capacity(block.len());
+        let mut current_item = Item::default();
+        let mut is_multiline_code = false;
+        for line in block {
+            if options.comments.is_attached(&line) {
+                current_item.comment.push(line);
+                is_multiline_code = false;
+            } else {
+                current_item.code.push(line.clone());
+                if line.contains('{') {
+                    is_multiline_code = true;
+                }
+                if !is_multiline_code || is_code_section_completed(&line) {
+                    items.push(std::mem::take(&mut current_item));
+                    is_multiline_code = false;
+                }
+            }
+        }
+        if !current_item.comment.is_empty() || !current_item.code.is_empty() {
+            items.push(current_item);
+        }
+        items
+    }
 }
 
 fn is_block_start(line: &str) -> bool {
@@ -62,54 +103,6 @@ fn is_block_start(line: &str) -> bool {
     false
 }
 
-#[derive(Default)]
-struct Item {
-    comment: Vec<String>,
-    code: Vec<String>,
-}
-
-/// Sorts a block of lines, keeping associated comments with their items.
-fn sort(block: Vec<String>, is_ignore_block_prev_line: bool) -> Vec<String> {
-    if is_ignore_block_prev_line || is_ignore_block(&block) {
-        return block;
-    }
-    let n = block.len();
-    let mut items = Vec::with_capacity(n);
-    let mut current_item = Item::default();
-    let mut is_multiline_code = false;
-    for line in block {
-        if is_single_line_comment(&line) {
-            current_item.comment.push(line);
-            is_multiline_code = false;
-        } else {
-            current_item.code.push(line.clone());
-            if line.contains('{') {
-                is_multiline_code = true;
-            }
-            if !is_multiline_code || is_code_section_completed(&line) {
-                items.push(std::mem::take(&mut current_item));
-                is_multiline_code = false;
-            }
-        }
-    }
-    let trailing_comments = std::mem::take(&mut current_item.comment);
-
-    items.sort_by(|a, b| a.code.cmp(&b.code));
-
-    let mut result = Vec::with_capacity(n);
-    for item in items {
-        result.extend(item.comment);
-        result.extend(item.code);
-    }
-    result.extend(trailing_comments);
-
-    result
-}
-
-fn is_single_line_comment(line: &str) -> bool {
-    line.trim().starts_with('#')
-}
-
 fn is_code_section_completed(line: &str) -> bool {
     // Split the line at the '#' character, take the first part, trim it, and check if it ends with '}'
     line.trim()
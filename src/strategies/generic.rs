@@ -1,80 +1,207 @@
-use std::io;
+use std::cmp::Ordering;
 
-use crate::{is_ignore_block, RE_KEEP_SORTED};
+use crate::options::SortOptions;
+use crate::strategies::{
+    is_end_directive, parse_directive, push_block, sort_items, Block, Item, SortStrategy,
+};
+use crate::{Error, Result};
 
-pub(crate) fn process(lines: Vec<String>) -> io::Result<Vec<String>> {
-    let mut output_lines: Vec<String> = Vec::new();
-    let mut block = Vec::new();
-    let mut is_sorting_block = false;
-    let mut is_ignore_block_prev_line = false;
+/// Blocks of lines after a `Keep sorted` directive, in any file.
+pub struct Generic;
 
-    for line in lines {
-        if RE_KEEP_SORTED.is_match(&line) {
-            if let Some(prev_line) = output_lines.last() {
-                is_ignore_block_prev_line = is_ignore_block(&[prev_line.clone()]);
-            }
-            is_sorting_block = true;
-            output_lines.push(line);
-        } else if is_sorting_block && line.trim().is_empty() {
-            block = sort(block, is_ignore_block_prev_line);
-            is_ignore_block_prev_line = false;
-            is_sorting_block = false;
-            output_lines.append(&mut block);
-            output_lines.push(line);
-        } else if is_sorting_block {
-            block.push(line);
-        } else {
-            output_lines.push(line);
-        }
+impl SortStrategy for Generic {
+    fn name(&self) -> &'static str {
+        "generic"
     }
 
-    if is_sorting_block {
-        block = sort(block, is_ignore_block_prev_line);
-        output_lines.append(&mut block);
+    fn description(&self) -> &'static str {
+        "Blocks of lines after a `Keep sorted` directive"
     }
 
-    Ok(output_lines)
-}
+    fn find_blocks(&self, lines: &[String], defaults: &SortOptions) -> Result<Vec<Block>> {
+        let mut blocks = Vec::new();
+        // Start of the block being sorted, after the directive and the skipped lines.
+        let mut block_start = None;
+        // Explicit blocks end with `keepsorted: end` rather than an empty line.
+        let mut is_explicit_block = false;
+        // Index of the line of the last directive.
+        let mut directive_line = 0;
+        let mut lines_to_skip = 0;
+        // Groups left in a `Keep sorted` block after the current one.
+        let mut groups_to_continue = 0;
+        let mut options = defaults.clone();
+        let mut is_ignore_block_prev_line = false;
 
-#[derive(Default)]
-struct Item {
-    comment: Vec<String>,
-    code: String,
-}
+        for (i, line) in lines.iter().enumerate() {
+            let is_until_line = options
+                .until
+                .as_ref()
+                .is_some_and(|until| until.is_match(line));
+            if !is_explicit_block && is_until_line {
+                if let Some(start) = block_start.take() {
+                    push_block(
+                        &mut blocks,
+                        lines,
+                        start..i,
+                        &options,
+                        is_ignore_block_prev_line,
+                    );
+                }
+            }
+
+            if is_end_directive(line, defaults) {
+                if let Some(start) = block_start.take() {
+                    push_block(
+                        &mut blocks,
+                        lines,
+                        start..i,
+                        &options,
+                        is_ignore_block_prev_line,
+                    );
+                }
+                is_explicit_block = false;
+            } else if block_start.is_some()
+                && lines_to_skip > 0
+                && (is_explicit_block || !line.trim().is_empty())
+            {
+                lines_to_skip -= 1;
+                block_start = Some(i + 1);
+            } else if is_explicit_block {
+                // The line is part of the block.
+            } else if let Some(directive) = parse_directive(line, i, defaults)? {
+                if let Some(start) = block_start.take() {
+                    push_block(
+                        &mut blocks,
+                        lines,
+                        start..i,
+                        &options,
+                        is_ignore_block_prev_line,
+                    );
+                }
+                is_ignore_block_prev_line = super::is_ignore_block_prev_line(lines, i, defaults);
+                block_start = Some(i + 1);
+                directive_line = i;
+                is_explicit_block = directive.is_explicit;
+                options = directive.options;
+                lines_to_skip = options.skip_lines;
+                groups_to_continue = options.groups.map_or(0, |groups| groups - 1);
+            } else if let Some(start) = block_start.filter(|_| line.trim().is_empty()) {
+                if is_group_continued(&lines[start..i]) {
+                    // Consecutive empty lines separate the same groups.
+                } else if options.until.is_some() || groups_to_continue > 0 {
+                    groups_to_continue = groups_to_continue.saturating_sub(1);
+                } else {
+                    push_block(
+                        &mut blocks,
+                        lines,
+                        start..i,
+                        &options,
+                        is_ignore_block_prev_line,
+                    );
+                    block_start = None;
+                }
+            }
+        }
+
+        if is_explicit_block {
+            return Err(Error::InvalidBlock {
+                line: directive_line + 1,
+                message: "missing end directive for the block started here".to_string(),
+            });
+        }
+        if let Some(until) = options.until.as_ref().filter(|_| block_start.is_some()) {
+            return Err(Error::InvalidBlock {
+                line: directive_line + 1,
+                message: format!(
+                    "no line matches the 'until' regex '{}' after the block started here",
+                    until.as_str()
+                ),
+            });
+        }
+        if let Some(start) = block_start {
+            push_block(
+                &mut blocks,
+                lines,
+                start..lines.len(),
+                &options,
+                is_ignore_block_prev_line,
+            );
+        }
 
-/// Sorts a block of lines, keeping associated comments with their items.
-fn sort(block: Vec<String>, is_ignore_block_prev_line: bool) -> Vec<String> {
-    if is_ignore_block_prev_line || is_ignore_block(&block) {
-        return block;
+        Ok(blocks)
     }
-    let n = block.len();
-    let mut items = Vec::with_capacity(n);
-    let mut current_item = Item::default();
-    for line in block {
-        if is_single_line_comment(&line) {
-            current_item.comment.push(line);
-        } else {
+
+    /// Keeps the comments with their items, and with the `group=indent` option
+    /// the lines indented deeper than an item with it.
+    fn split_items(&self, block: Vec<String>, options: &SortOptions) -> Vec<Item> {
+        let mut items: Vec<Item> = Vec::with_capacity(block.len());
+        let mut comment = Vec::new();
+        // Indentation of the first item line, deeper lines continue the previous item.
+        let mut item_indent = None;
+        for line in block {
+            let is_continuation = options.group_by_indent
+                && comment.is_empty()
+                && item_indent.is_some_and(|item_indent| indentation(&line) > item_indent);
+            if is_continuation {
+                if let Some(item) = items.last_mut() {
+                    item.code.push(line);
+                }
+            } else if options.comments.is_attached(&line) {
+                comment.push(line);
+            } else {
+                item_indent.get_or_insert_with(|| indentation(&line));
+                items.push(Item {
+                    comment: std::mem::take(&mut comment),
+                    code: vec![line],
+                });
+            }
+        }
+        if !comment.is_empty() {
             items.push(Item {
-                comment: std::mem::take(&mut current_item.comment),
-                code: line,
+                comment,
+                code: Vec::new(),
             });
         }
+        items
+    }
+
+    /// Compares the first lines of the items with the `by_regex` option,
+    /// then the lines continuing them.
+    fn compare_items(&self, a: &Item, b: &Item, options: &SortOptions) -> Ordering {
+        options
+            .compare_items(a.first_line(), b.first_line())
+            .then_with(|| options.compare_lines(continuation(a), continuation(b)))
     }
-    let trailing_comments = std::mem::take(&mut current_item.comment);
 
-    items.sort_by(|a, b| a.code.cmp(&b.code));
+    /// Sorts every group of lines separated by empty lines independently,
+    /// keeping the empty lines in place.
+    fn sort_block(&self, block: Vec<String>, options: &SortOptions) -> Vec<String> {
+        let mut result = Vec::with_capacity(block.len());
+        let mut group = Vec::new();
+        for line in block {
+            if line.trim().is_empty() {
+                result.extend(sort_items(self, std::mem::take(&mut group), options));
+                result.push(line);
+            } else {
+                group.push(line);
+            }
+        }
+        result.extend(sort_items(self, group, options));
 
-    let mut result = Vec::with_capacity(n);
-    for item in items {
-        result.extend(item.comment);
-        result.push(item.code);
+        result
     }
-    result.extend(trailing_comments);
+}
+
+/// Tells whether the block already continues past an empty line.
+fn is_group_continued(block: &[String]) -> bool {
+    block.last().is_some_and(|line| line.trim().is_empty())
+}
 
-    result
+/// Lines indented deeper than the item with the `group=indent` option.
+fn continuation(item: &Item) -> &[String] {
+    item.code.get(1..).unwrap_or_default()
 }
 
-fn is_single_line_comment(line: &str) -> bool {
-    let trimmed = line.trim();
-    trimmed.starts_with('#') || trimmed.starts_with("//")
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start().len()
 }
@@ -1,82 +1,63 @@
-use std::io;
+use crate::options::SortOptions;
+use crate::strategies::{is_ignore_block_prev_line, push_block, sort_items, Block, SortStrategy};
+use crate::{Feature, FilePattern, Result};
 
-use crate::is_ignore_block;
+/// Blocks of patterns separated by empty lines, in `.gitignore` and `CODEOWNERS` files.
+pub struct Gitignore;
 
-pub(crate) fn process(lines: Vec<String>) -> io::Result<Vec<String>> {
-    let mut output_lines = Vec::new();
-    let mut block = Vec::new();
-    let mut is_sorting_block = false;
-    let mut is_ignore_block_prev_line = false;
-
-    for line in lines {
-        if !line.trim().is_empty() {
-            if is_single_line_comment(&line) {
-                // Skip opening comment.
-                output_lines.push(line);
-            } else {
-                if let Some(prev_line) = output_lines.last() {
-                    is_ignore_block_prev_line = is_ignore_block(&[prev_line.clone()]);
-                }
-                is_sorting_block = true;
-                block.push(line);
-            }
-        } else if is_sorting_block {
-            block = sort(block, is_ignore_block_prev_line);
-            is_ignore_block_prev_line = false;
-            is_sorting_block = false;
-            output_lines.append(&mut block);
-            output_lines.push(line);
-        } else {
-            output_lines.push(line);
-        }
+impl SortStrategy for Gitignore {
+    fn name(&self) -> &'static str {
+        "gitignore"
     }
 
-    if is_sorting_block {
-        block = sort(block, is_ignore_block_prev_line);
-        output_lines.append(&mut block);
+    fn description(&self) -> &'static str {
+        "Blocks of patterns separated by empty lines"
     }
 
-    Ok(output_lines)
-}
+    fn file_patterns(&self) -> &'static [(FilePattern, Option<Feature>)] {
+        &[
+            (FilePattern::Name(".gitignore"), Some(Feature::Gitignore)),
+            (FilePattern::Name("CODEOWNERS"), Some(Feature::Codeowners)),
+        ]
+    }
 
-#[derive(Default)]
-struct Item {
-    comment: Vec<String>,
-    code: String,
-}
+    fn find_blocks(&self, lines: &[String], defaults: &SortOptions) -> Result<Vec<Block>> {
+        let mut blocks = Vec::new();
+        // Start of the block being sorted, at its first pattern.
+        let mut block_start = None;
+        let mut is_ignore_block = false;
 
-/// Sorts a block of lines, keeping associated comments with their items.
-fn sort(block: Vec<String>, is_ignore_block_prev_line: bool) -> Vec<String> {
-    if is_ignore_block_prev_line || is_ignore_block(&block) {
-        return block;
-    }
-    let n = block.len();
-    let mut items = Vec::with_capacity(n);
-    let mut current_item = Item::default();
-    for line in block {
-        if is_single_line_comment(&line) {
-            current_item.comment.push(line);
-        } else {
-            items.push(Item {
-                comment: std::mem::take(&mut current_item.comment),
-                code: line,
-            });
+        for (i, line) in lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                if let Some(start) = block_start.take() {
+                    push_block(&mut blocks, lines, start..i, defaults, is_ignore_block);
+                }
+            } else if block_start.is_none() && !defaults.comments.is_comment(line) {
+                // Opening comments are not part of the block.
+                is_ignore_block = is_ignore_block_prev_line(lines, i, defaults);
+                block_start = Some(i);
+            }
         }
-    }
-    let trailing_comments = std::mem::take(&mut current_item.comment);
 
-    items.sort_by(|a, b| a.code.cmp(&b.code));
+        if let Some(start) = block_start {
+            push_block(
+                &mut blocks,
+                lines,
+                start..lines.len(),
+                defaults,
+                is_ignore_block,
+            );
+        }
 
-    let mut result = Vec::with_capacity(n);
-    for item in items {
-        result.extend(item.comment);
-        result.push(item.code);
+        Ok(blocks)
     }
-    result.extend(trailing_comments);
 
-    result
-}
-
-fn is_single_line_comment(line: &str) -> bool {
-    line.trim().starts_with('#')
+    /// Moves the comments inside the block before it, and sorts the patterns.
+    fn sort_block(&self, block: Vec<String>, options: &SortOptions) -> Vec<String> {
+        let (mut comments, patterns): (Vec<String>, Vec<String>) = block
+            .into_iter()
+            .partition(|line| options.comments.is_comment(line));
+        comments.extend(sort_items(self, patterns, options));
+        comments
+    }
 }
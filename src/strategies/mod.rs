@@ -1,5 +1,312 @@
+use std::any::Any;
+use std::cmp::Ordering;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::options::SortOptions;
+use crate::{
+    captures_directive, is_directive, is_ignore_block, sort_block, Change, Error, Feature,
+    Features, FilePattern, Result, Strategy, RE_KEEP_SORTED, RE_KEEP_SORTED_END,
+};
+
 pub mod bazel;
 pub mod cargo_toml;
 pub mod generic;
 pub mod gitignore;
 pub mod rust_derive;
+
+/// A way of finding and sorting blocks in a kind of file.
+///
+/// Only `name`, `description` and `find_blocks` are required: by default a block
+/// is split into single-line items with the comments above them, which are
+/// compared line by line with the options of the block.
+pub trait SortStrategy: Any + Sync {
+    /// Name of the strategy as used in the configuration file.
+    fn name(&self) -> &'static str;
+
+    /// Short description of what the strategy sorts.
+    fn description(&self) -> &'static str;
+
+    /// Files the strategy is picked for by default, with the feature flag they
+    /// require if any.
+    fn file_patterns(&self) -> &'static [(FilePattern, Option<Feature>)] {
+        &[]
+    }
+
+    /// Tells whether the strategy is picked for the file by default.
+    /// The file does not need to exist.
+    fn detect(&self, path: &Path, features: &Features) -> bool {
+        self.file_patterns().iter().any(|(pattern, feature)| {
+            pattern.matches(path) && feature.iter().all(|feature| features.is_enabled(*feature))
+        })
+    }
+
+    /// Finds the blocks to sort, in order and without overlap. The defaults are
+    /// the options of every block before its directive is parsed.
+    fn find_blocks(&self, lines: &[String], defaults: &SortOptions) -> Result<Vec<Block>>;
+
+    /// Splits a block into the items to sort. Comments after the last item
+    /// are returned as a last item without code, which stays at the end.
+    fn split_items(&self, block: Vec<String>, options: &SortOptions) -> Vec<Item> {
+        let mut items = Vec::with_capacity(block.len());
+        let mut comment = Vec::new();
+        for line in block {
+            if options.comments.is_attached(&line) {
+                comment.push(line);
+            } else {
+                items.push(Item {
+                    comment: std::mem::take(&mut comment),
+                    code: vec![line],
+                });
+            }
+        }
+        if !comment.is_empty() {
+            items.push(Item {
+                comment,
+                code: Vec::new(),
+            });
+        }
+        items
+    }
+
+    fn compare_items(&self, a: &Item, b: &Item, options: &SortOptions) -> Ordering {
+        options.compare_lines(&a.code, &b.code)
+    }
+
+    /// Sorts the lines of a block found by `find_blocks`.
+    fn sort_block(&self, block: Vec<String>, options: &SortOptions) -> Vec<String> {
+        sort_items(self, block, options)
+    }
+}
+
+/// A block of lines to sort.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Block {
+    /// 0-based range of the input lines of the block.
+    pub lines: Range<usize>,
+    /// Options of the block, eg. set on its directive.
+    pub options: SortOptions,
+}
+
+/// An item of a block, moved as a whole with the comments above it.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Item {
+    pub comment: Vec<String>,
+    /// Lines of the item, usually a single one.
+    pub code: Vec<String>,
+}
+
+impl Item {
+    /// The first line of the item, or an empty string for trailing comments.
+    pub fn first_line(&self) -> &str {
+        self.code.first().map_or("", String::as_str)
+    }
+}
+
+/// Sorts a block by splitting it into items and comparing them with the
+/// strategy, and merges duplicate items with the `unique` option.
+pub fn sort_items<S>(strategy: &S, block: Vec<String>, options: &SortOptions) -> Vec<String>
+where
+    S: SortStrategy + ?Sized,
+{
+    let n = block.len();
+    let mut items = strategy.split_items(block, options);
+    let trailing_comments = match items.last() {
+        Some(item) if item.code.is_empty() => items.pop(),
+        _ => None,
+    };
+
+    items.sort_by(|a, b| strategy.compare_items(a, b, options));
+    if options.unique {
+        items = dedup(items);
+    }
+
+    let mut result = Vec::with_capacity(n);
+    for item in items.into_iter().chain(trailing_comments) {
+        result.extend(item.comment);
+        result.extend(item.code);
+    }
+
+    result
+}
+
+/// Removes the items with the same code as a previous item,
+/// appending their comments to the comments of the kept item.
+fn dedup(items: Vec<Item>) -> Vec<Item> {
+    let mut result: Vec<Item> = Vec::with_capacity(items.len());
+    for item in items {
+        match result.iter_mut().find(|kept| kept.code == item.code) {
+            Some(kept) => {
+                for comment in item.comment {
+                    if !kept.comment.contains(&comment) {
+                        kept.comment.push(comment);
+                    }
+                }
+            }
+            None => result.push(item),
+        }
+    }
+    result
+}
+
+/// Sorts the blocks found by the strategy and records the modified ones in `changes`.
+/// Blocks are cut around the off regions, whose lines are left as they are.
+pub(crate) fn process(
+    strategy: &dyn SortStrategy,
+    lines: Vec<String>,
+    defaults: &SortOptions,
+    off_regions: &[Range<usize>],
+    changes: &mut Vec<Change>,
+) -> Result<Vec<String>> {
+    let blocks = strategy.find_blocks(&lines, defaults)?;
+    let mut output_lines = Vec::with_capacity(lines.len());
+    let mut lines = lines.into_iter();
+    let mut n = 0;
+    for block in blocks {
+        if block.lines.start < n {
+            // Overlapping blocks are left as they are.
+            continue;
+        }
+        for range in cut_off_regions(block.lines, off_regions) {
+            output_lines.extend(lines.by_ref().take(range.start - n));
+            let lines: Vec<String> = lines.by_ref().take(range.len()).collect();
+            n = range.start + lines.len();
+            output_lines.extend(sort_block(lines, n, &block.options, changes, |lines| {
+                strategy.sort_block(lines, &block.options)
+            }));
+        }
+    }
+    output_lines.extend(lines);
+
+    Ok(output_lines)
+}
+
+/// Splits the range of a block into the parts outside the off regions.
+fn cut_off_regions(range: Range<usize>, off_regions: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut parts = Vec::new();
+    let mut start = range.start;
+    for off in off_regions {
+        if off.end <= start || off.start >= range.end {
+            continue;
+        }
+        if off.start > start {
+            parts.push(start..off.start);
+        }
+        start = off.end;
+    }
+    if start < range.end {
+        parts.push(start..range.end);
+    }
+    parts
+}
+
+/// A `Keep sorted` directive starting a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Directive {
+    /// Options of the block, on top of the defaults.
+    pub options: SortOptions,
+    /// The block ends with an end directive rather than with the strategy's own
+    /// delimiter, eg. `keepsorted: keep sorted start`.
+    pub is_explicit: bool,
+}
+
+/// Parses the `Keep sorted` directive at the input line `i`, if any.
+/// Invalid options are reported with the line of the directive.
+pub fn parse_directive(line: &str, i: usize, defaults: &SortOptions) -> Result<Option<Directive>> {
+    let Some(captures) = captures_directive(&RE_KEEP_SORTED, line, &defaults.comments) else {
+        return Ok(None);
+    };
+    let options = captures
+        .name("options")
+        .map_or("", |options| options.as_str());
+    let is_google = captures.name("google").is_some();
+    let mut defaults = defaults.clone();
+    // Google keep-sorted keeps indented lines with the item above them by default.
+    defaults.group_by_indent |= is_google;
+    let options = defaults
+        .parse(options)
+        .map_err(|message| Error::InvalidOption {
+            line: i + 1,
+            message,
+        })?;
+    Ok(Some(Directive {
+        options,
+        is_explicit: captures.name("start").is_some() || is_google,
+    }))
+}
+
+/// Tells whether the line is the end directive of an explicit block.
+pub fn is_end_directive(line: &str, options: &SortOptions) -> bool {
+    is_directive(&RE_KEEP_SORTED_END, line, &options.comments)
+}
+
+/// Adds the block to sort, unless an `ignore block` directive is inside it or
+/// right before its directive.
+pub fn push_block(
+    blocks: &mut Vec<Block>,
+    lines: &[String],
+    range: Range<usize>,
+    options: &SortOptions,
+    is_ignore_block_prev_line: bool,
+) {
+    if is_ignore_block_prev_line || is_ignore_block(&lines[range.clone()], &options.comments) {
+        return;
+    }
+    blocks.push(Block {
+        lines: range,
+        options: options.clone(),
+    });
+}
+
+/// Tells whether the line before the input line `i` is an `ignore block` directive.
+pub fn is_ignore_block_prev_line(lines: &[String], i: usize, options: &SortOptions) -> bool {
+    i > 0 && is_ignore_block(&lines[i - 1..i], &options.comments)
+}
+
+/// Strategies to pick from by name or by file: the built-in ones and the ones
+/// registered by the application.
+#[derive(Clone, Debug)]
+pub struct Registry {
+    strategies: Vec<Strategy>,
+}
+
+impl Default for Registry {
+    /// The built-in strategies.
+    fn default() -> Self {
+        Self {
+            strategies: Strategy::ALL.to_vec(),
+        }
+    }
+}
+
+impl Registry {
+    /// Adds a strategy, which is picked before the ones registered earlier and
+    /// the built-in ones, both by name and by file.
+    pub fn register(&mut self, strategy: &'static dyn SortStrategy) -> Strategy {
+        let strategy = Strategy::new(strategy);
+        self.strategies.insert(0, strategy);
+        strategy
+    }
+
+    pub fn strategies(&self) -> &[Strategy] {
+        &self.strategies
+    }
+
+    /// Finds a strategy by its name as used in the configuration file.
+    pub fn get(&self, name: &str) -> Option<Strategy> {
+        self.strategies
+            .iter()
+            .find(|strategy| strategy.name() == name)
+            .copied()
+    }
+
+    /// Picks the strategy for the file based on its path and the enabled feature flags.
+    /// Files not detected by any strategy use `Strategy::GENERIC`.
+    pub fn detect(&self, path: &Path, features: &Features) -> Strategy {
+        self.strategies
+            .iter()
+            .find(|strategy| strategy.implementation().detect(path, features))
+            .map_or(Strategy::GENERIC, |strategy| *strategy)
+    }
+}
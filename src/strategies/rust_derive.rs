@@ -1,9 +1,9 @@
-use crate::Strategy;
 use once_cell::sync::Lazy;
 use regex::Regex;
-use std::io;
 
-use crate::is_ignore_block;
+use crate::options::SortOptions;
+use crate::strategies::{is_ignore_block_prev_line, push_block, Block, SortStrategy};
+use crate::{Feature, FilePattern, Result};
 
 static RE_DERIVE_BEGIN: Lazy<Regex> = Lazy::new(re_derive_begin);
 static RE_DERIVE_END: Lazy<Regex> = Lazy::new(re_derive_end);
@@ -12,106 +12,146 @@ static RE_DERIVE_END: Lazy<Regex> = Lazy::new(re_derive_end);
 const STAY_ONE_LINE_LEN: usize = 97;
 const BREAK_INTO_MANY_LINES_LEN: usize = 101;
 
-pub(crate) fn process(lines: Vec<String>, strategy: Strategy) -> io::Result<Vec<String>> {
-    let mut output_lines: Vec<String> = Vec::new();
-    let mut block = Vec::new();
-    let mut is_sorting_block = false;
-    let mut is_ignore_block_prev_line = false;
-
-    for line in lines {
-        let mut is_derive_begin = false;
-        if RE_DERIVE_BEGIN.is_match(&line) {
-            if let Some(prev_line) = output_lines.last() {
-                is_ignore_block_prev_line = is_ignore_block(&[prev_line.clone()]);
-            }
-            is_derive_begin = true;
-            is_sorting_block = true;
-            block.push(line.clone());
+/// Traits in `#[derive(...)]` attributes of Rust files.
+pub struct RustDerive {
+    order: TraitOrder,
+}
+
+enum TraitOrder {
+    Alphabetical,
+    /// Standard traits first, then alphabetically.
+    Canonical,
+}
+
+pub const ALPHABETICAL: RustDerive = RustDerive {
+    order: TraitOrder::Alphabetical,
+};
+
+pub const CANONICAL: RustDerive = RustDerive {
+    order: TraitOrder::Canonical,
+};
+
+impl SortStrategy for RustDerive {
+    fn name(&self) -> &'static str {
+        match self.order {
+            TraitOrder::Alphabetical => "rust_derive_alphabetical",
+            TraitOrder::Canonical => "rust_derive_canonical",
         }
-        let line_without_comment = line.trim().split("//").next().unwrap_or("").trim();
-        if is_sorting_block && RE_DERIVE_END.is_match(line_without_comment) {
-            if !is_derive_begin {
-                block.push(line.clone());
-            }
-            block = sort(block, is_ignore_block_prev_line, strategy);
-            is_ignore_block_prev_line = false;
-            is_sorting_block = false;
-            output_lines.append(&mut block);
-        } else if is_sorting_block {
-            if !is_derive_begin {
-                block.push(line);
+    }
+
+    fn description(&self) -> &'static str {
+        match self.order {
+            TraitOrder::Alphabetical => "Traits in `#[derive(...)]`, alphabetically",
+            TraitOrder::Canonical => {
+                "Traits in `#[derive(...)]`, standard traits first, then alphabetically"
             }
-        } else {
-            output_lines.push(line);
         }
     }
 
-    if is_sorting_block {
-        block = sort(block, is_ignore_block_prev_line, strategy);
-        output_lines.append(&mut block);
+    fn file_patterns(&self) -> &'static [(FilePattern, Option<Feature>)] {
+        match self.order {
+            TraitOrder::Alphabetical => &[(
+                FilePattern::Extension("rs"),
+                Some(Feature::RustDeriveAlphabetical),
+            )],
+            TraitOrder::Canonical => &[(
+                FilePattern::Extension("rs"),
+                Some(Feature::RustDeriveCanonical),
+            )],
+        }
     }
 
-    Ok(output_lines)
-}
-
-fn sort(block: Vec<String>, is_ignore_block_prev_line: bool, strategy: Strategy) -> Vec<String> {
-    if is_ignore_block_prev_line || is_ignore_block(&block) {
-        return block;
-    }
-    let line: String = block
-        .iter()
-        .map(|line| line.trim_end_matches('\n'))
-        .collect();
-    let line = format!("{}\n", line);
-    let line_without_comment = line.trim().split("//").next().unwrap_or("").trim();
-
-    // Check if the line contains a #[derive(...)] statement
-    if let Some(derive_range) = line_without_comment.find("#[derive(").and_then(|start| {
-        let end = line_without_comment[start..].find(")]")?;
-        Some(start + 9..start + end)
-    }) {
-        let derive_content = &line_without_comment[derive_range.clone()];
-        let mut traits: Vec<&str> = derive_content
-            .split(',')
-            .map(str::trim)
-            .filter(|t| !t.is_empty())
-            .collect();
+    /// Every `#[derive(...)]` attribute is a block, with the default options.
+    fn find_blocks(&self, lines: &[String], _: &SortOptions) -> Result<Vec<Block>> {
+        let options = SortOptions::default();
+        let mut blocks = Vec::new();
+        let mut block_start = None;
+        let mut is_ignore_block = false;
+
+        for (i, line) in lines.iter().enumerate() {
+            if RE_DERIVE_BEGIN.is_match(line) {
+                is_ignore_block = is_ignore_block_prev_line(lines, i, &options);
+                block_start.get_or_insert(i);
+            }
+            let line_without_comment = line.trim().split("//").next().unwrap_or("").trim();
+            if let Some(start) =
+                block_start.filter(|_| RE_DERIVE_END.is_match(line_without_comment))
+            {
+                push_block(&mut blocks, lines, start..i + 1, &options, is_ignore_block);
+                block_start = None;
+            }
+        }
 
-        match strategy {
-            Strategy::RustDeriveAlphabetical => traits = aphabetical_sort(traits),
-            Strategy::RustDeriveCanonical => traits = canonical_sort(traits),
-            _ => return block,
+        if let Some(start) = block_start {
+            push_block(
+                &mut blocks,
+                lines,
+                start..lines.len(),
+                &options,
+                is_ignore_block,
+            );
         }
 
-        let sorted_traits = traits.join(", ");
-        let new_derive = format!("#[derive({})]", sorted_traits);
+        Ok(blocks)
+    }
 
-        // Preserve the prefix and suffix whitespace
-        let prefix_whitespace = &line[..line.find(line_without_comment).unwrap_or(0)];
-        let suffix_whitespace =
-            &line[line_without_comment.len() + line.find(line_without_comment).unwrap_or(0)..];
+    /// Sorts the traits of the attribute, and writes it on one line or on
+    /// several lines depending on its length.
+    fn sort_block(&self, block: Vec<String>, _: &SortOptions) -> Vec<String> {
+        let line: String = block
+            .iter()
+            .map(|line| line.trim_end_matches('\n'))
+            .collect();
+        let line = format!("{}\n", line);
+        let line_without_comment = line.trim().split("//").next().unwrap_or("").trim();
 
-        let new_line = format!("{}{}{}", prefix_whitespace, new_derive, suffix_whitespace);
-        if new_line.len() <= STAY_ONE_LINE_LEN {
-            return vec![new_line];
-        }
+        // Check if the line contains a #[derive(...)] statement
+        if let Some(derive_range) = line_without_comment.find("#[derive(").and_then(|start| {
+            let end = line_without_comment[start..].find(")]")?;
+            Some(start + 9..start + end)
+        }) {
+            let derive_content = &line_without_comment[derive_range.clone()];
+            let mut traits: Vec<&str> = derive_content
+                .split(',')
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .collect();
+
+            traits = match self.order {
+                TraitOrder::Alphabetical => aphabetical_sort(traits),
+                TraitOrder::Canonical => canonical_sort(traits),
+            };
+
+            let sorted_traits = traits.join(", ");
+            let new_derive = format!("#[derive({})]", sorted_traits);
+
+            // Preserve the prefix and suffix whitespace
+            let prefix_whitespace = &line[..line.find(line_without_comment).unwrap_or(0)];
+            let suffix_whitespace =
+                &line[line_without_comment.len() + line.find(line_without_comment).unwrap_or(0)..];
+
+            let new_line = format!("{}{}{}", prefix_whitespace, new_derive, suffix_whitespace);
+            if new_line.len() <= STAY_ONE_LINE_LEN {
+                return vec![new_line];
+            }
 
-        let mid_line = format!("{}    {},", prefix_whitespace, sorted_traits);
-        let mut result = vec![format!("{}#[derive(\n", prefix_whitespace)];
+            let mid_line = format!("{}    {},", prefix_whitespace, sorted_traits);
+            let mut result = vec![format!("{}#[derive(\n", prefix_whitespace)];
 
-        if mid_line.len() <= BREAK_INTO_MANY_LINES_LEN {
-            result.push(format!("{}\n{})]\n", mid_line, prefix_whitespace));
-        } else {
-            for trait_item in traits {
-                result.push(format!("{}    {},\n", prefix_whitespace, trait_item));
+            if mid_line.len() <= BREAK_INTO_MANY_LINES_LEN {
+                result.push(format!("{}\n{})]\n", mid_line, prefix_whitespace));
+            } else {
+                for trait_item in traits {
+                    result.push(format!("{}    {},\n", prefix_whitespace, trait_item));
+                }
+                result.push(format!("{})]\n", prefix_whitespace));
             }
-            result.push(format!("{})]\n", prefix_whitespace));
+
+            return result;
         }
 
-        return result;
+        block
     }
-
-    block
 }
 
 fn extract_last_token(s: &str) -> &str {
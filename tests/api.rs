@@ -0,0 +1,87 @@
+use keepsorted::{sort_str, Error, Features, Options, Strategy};
+use std::path::Path;
+
+#[test]
+fn sort_str_reports_changed_blocks() {
+    let content =
+        "# Keep sorted\nb\na\n\n# Keep sorted\nc\nd\n\n# Keep sorted unique=yes\ne\ne\nf\n";
+    let outcome = sort_str(content, &Options::new(Strategy::GENERIC)).unwrap();
+    assert_eq!(
+        outcome.text,
+        "# Keep sorted\na\nb\n\n# Keep sorted\nc\nd\n\n# Keep sorted unique=yes\ne\nf\n"
+    );
+    assert!(outcome.is_changed());
+    let changes: Vec<_> = outcome
+        .changes
+        .iter()
+        .map(|block| {
+            (
+                block.start_line,
+                block.end_line,
+                block.strategy,
+                block.has_duplicates,
+            )
+        })
+        .collect();
+    assert_eq!(
+        changes,
+        [
+            (2, 3, Strategy::GENERIC, false),
+            (10, 12, Strategy::GENERIC, true),
+        ]
+    );
+}
+
+#[test]
+fn sort_str_sorted_content() {
+    let content = "block = [\n    # Keep sorted\n    \"a\",\n    \"b\",\n]";
+    let outcome = sort_str(content, &Options::new(Strategy::BAZEL)).unwrap();
+    assert_eq!(outcome.text, content);
+    assert!(!outcome.is_changed());
+}
+
+#[test]
+fn sort_str_with_options_for_path() {
+    let options = Options::for_path(Path::new("schema.sql"), &Features::default());
+    assert_eq!(options.strategy, Strategy::GENERIC);
+    let outcome = sort_str("-- Keep sorted\r\nb\r\na\r\n", &options).unwrap();
+    assert_eq!(outcome.text, "-- Keep sorted\r\na\r\nb\r\n");
+
+    let mut options = options;
+    options.sort.reverse = true;
+    let outcome = sort_str("-- Keep sorted\na\nb\n", &options).unwrap();
+    assert_eq!(outcome.text, "-- Keep sorted\nb\na\n");
+}
+
+#[test]
+fn sort_str_invalid_option() {
+    let err = sort_str(
+        "a\n# Keep sorted numeric=maybe\nb\na\n",
+        &Options::new(Strategy::GENERIC),
+    )
+    .expect_err("Invalid option was accepted");
+    match &err {
+        Error::InvalidOption { line, message } => {
+            assert_eq!(*line, 2);
+            assert_eq!(
+                message,
+                "invalid value 'maybe' for option 'numeric', expected 'yes' or 'no'"
+            );
+        }
+        _ => panic!("Unexpected error: {:?}", err),
+    }
+    assert_eq!(
+        err.to_string(),
+        "line 2: invalid value 'maybe' for option 'numeric', expected 'yes' or 'no'"
+    );
+}
+
+#[test]
+fn sort_str_keeps_line_endings_with_their_lines() {
+    let outcome = sort_str(
+        "x\r\ny\n# Keep sorted\r\nb\na\r\n",
+        &Options::new(Strategy::GENERIC),
+    )
+    .unwrap();
+    assert_eq!(outcome.text, "x\r\ny\n# Keep sorted\r\na\r\nb\n");
+}
@@ -1,12 +1,12 @@
 #[macro_use]
 mod common;
 
-use keepsorted::Strategy::Bazel;
+use keepsorted::Strategy;
 
 #[test]
 fn bazel_single_block() {
     test_inner!(
-        Bazel,
+        Strategy::BAZEL,
         r#"
 block = [
     # Keep sorted.
@@ -27,7 +27,7 @@ block = [
 #[test]
 fn bazel_inline_comment() {
     test_inner!(
-        Bazel,
+        Strategy::BAZEL,
         r#"
 block = [
     # Keep sorted.
@@ -52,7 +52,7 @@ block = [
 #[test]
 fn bazel_inline_comment_with_braces() {
     test_inner!(
-        Bazel,
+        Strategy::BAZEL,
         r#"
 block = [
     # Keep sorted.
@@ -77,7 +77,7 @@ block = [
 #[test]
 fn bazel_multi_line_comment() {
     test_inner!(
-        Bazel,
+        Strategy::BAZEL,
         r#"
 block = [
     # Keep sorted.
@@ -106,7 +106,7 @@ block = [
 #[test]
 fn bazel_multi_line_trailing_comment() {
     test_inner!(
-        Bazel,
+        Strategy::BAZEL,
         r#"
 block = [
     # Keep sorted.
@@ -131,7 +131,7 @@ block = [
 #[test]
 fn bazel_several_multi_line_comments() {
     test_inner!(
-        Bazel,
+        Strategy::BAZEL,
         r#"
 block = [
     # Keep sorted.
@@ -164,7 +164,7 @@ block = [
 #[test]
 fn bazel_single_block_with_comment() {
     test_inner!(
-        Bazel,
+        Strategy::BAZEL,
         r#"
 block = [
     # Keep sorted.
@@ -193,7 +193,7 @@ block = [
 #[test]
 fn bazel_blocks() {
     test_inner!(
-        Bazel,
+        Strategy::BAZEL,
         r#"
 block_1 = [
     # Keep sorted.
@@ -222,7 +222,7 @@ block_2 = [
 #[test]
 fn bazel_ignore_file() {
     test_inner!(
-        Bazel,
+        Strategy::BAZEL,
         r#"
 # keepsorted: ignore file
 block_1 = [
@@ -255,7 +255,7 @@ block_2 = [
 #[test]
 fn bazel_ignore_block_inside() {
     test_inner!(
-        Bazel,
+        Strategy::BAZEL,
         r#"
 block_1 = [
     # Keep sorted.
@@ -288,7 +288,7 @@ block_2 = [
 #[test]
 fn bazel_ignore_block_before() {
     test_inner!(
-        Bazel,
+        Strategy::BAZEL,
         r#"
 block_1 = [
     # keepsorted: ignore block
@@ -321,7 +321,7 @@ block_2 = [
 #[test]
 fn bazel_blocks_with_select() {
     test_inner!(
-        Bazel,
+        Strategy::BAZEL,
         r#"
 deps = [
     # Keep sorted.
@@ -364,7 +364,7 @@ deps = [
 #[test]
 fn bazel_order() {
     test_inner!(
-        Bazel,
+        Strategy::BAZEL,
         r#"
 block = [
     # Keep sorted.
@@ -411,3 +411,289 @@ block = [
         "#
     );
 }
+
+#[test]
+fn bazel_google_keep_sorted_markers() {
+    test_inner!(
+        Strategy::BAZEL,
+        r#"
+block = [
+    # keep-sorted start
+    "b",
+    "a",
+    # keep-sorted end
+]
+        "#,
+        r#"
+block = [
+    # keep-sorted start
+    "a",
+    "b",
+    # keep-sorted end
+]
+        "#
+    );
+}
+
+#[test]
+fn bazel_skip_lines_option() {
+    test_inner!(
+        Strategy::BAZEL,
+        r#"
+block = [
+    # Keep sorted skip_lines=1
+    "first",
+    "b",
+    "a",
+]
+        "#,
+        r#"
+block = [
+    # Keep sorted skip_lines=1
+    "first",
+    "a",
+    "b",
+]
+        "#
+    );
+}
+
+#[test]
+fn bazel_case_insensitive_option() {
+    test_inner!(
+        Strategy::BAZEL,
+        r#"
+block = [
+    # Keep sorted case=insensitive
+    "Z",
+    "b",
+    "a",
+    "B",
+]
+        "#,
+        r#"
+block = [
+    # Keep sorted case=insensitive
+    "a",
+    "B",
+    "b",
+    "Z",
+]
+        "#
+    );
+}
+
+#[test]
+fn bazel_numeric_option() {
+    test_inner!(
+        Strategy::BAZEL,
+        r#"
+block = [
+    # Keep sorted numeric=yes
+    ":lib10",
+    ":lib2",
+    "@crate_index//:base64",
+    "@crate_index//:base32",
+]
+        "#,
+        r#"
+block = [
+    # Keep sorted numeric=yes
+    ":lib2",
+    ":lib10",
+    "@crate_index//:base32",
+    "@crate_index//:base64",
+]
+        "#
+    );
+}
+
+#[test]
+fn bazel_unique_option() {
+    test_inner!(
+        Strategy::BAZEL,
+        r#"
+block = [
+    # Keep sorted unique=yes
+    ":b",
+    "a",
+    # Comment about b.
+    ":b",
+    "a",  # Not a duplicate.
+    "a",
+]
+        "#,
+        r#"
+block = [
+    # Keep sorted unique=yes
+    "a",
+    "a",  # Not a duplicate.
+    # Comment about b.
+    ":b",
+]
+        "#
+    );
+}
+
+#[test]
+fn bazel_reverse_option() {
+    test_inner!(
+        Strategy::BAZEL,
+        r#"
+block = [
+    # Keep sorted reverse=yes
+    "a",
+    ":b",
+    # Comment about //c.
+    "//c",
+    ":a",
+]
+        "#,
+        r#"
+block = [
+    # Keep sorted reverse=yes
+    # Comment about //c.
+    "//c",
+    ":b",
+    ":a",
+    "a",
+]
+        "#
+    );
+}
+
+#[test]
+fn bazel_off_on_region() {
+    test_inner!(
+        Strategy::BAZEL,
+        r#"
+block = [
+    # Keep sorted.
+    "b",
+    "a",
+]
+# keepsorted: off
+block = [
+    # Keep sorted.
+    "d",
+    "c",
+]
+# keepsorted: on
+block = [
+    # Keep sorted.
+    "f",
+    "e",
+]
+        "#,
+        r#"
+block = [
+    # Keep sorted.
+    "a",
+    "b",
+]
+# keepsorted: off
+block = [
+    # Keep sorted.
+    "d",
+    "c",
+]
+# keepsorted: on
+block = [
+    # Keep sorted.
+    "e",
+    "f",
+]
+        "#
+    );
+}
+
+#[test]
+fn bazel_off_on_region_in_list() {
+    test_inner!(
+        Strategy::BAZEL,
+        r#"
+block = [
+    # Keep sorted.
+    "d",
+    "c",
+    # keepsorted: off
+    "z",
+    "y",
+    # keepsorted: on
+    "b",
+    "a",
+]
+        "#,
+        r#"
+block = [
+    # Keep sorted.
+    "c",
+    "d",
+    # keepsorted: off
+    "z",
+    "y",
+    # keepsorted: on
+    "a",
+    "b",
+]
+        "#
+    );
+}
+
+#[test]
+fn bazel_explicit_end() {
+    test_inner!(
+        Strategy::BAZEL,
+        r#"
+block = [
+    # keep-sorted start
+    "d",
+    "c",
+    "b",
+    # keep-sorted end
+    "z",
+    "a",
+]
+        "#,
+        r#"
+block = [
+    # keep-sorted start
+    "b",
+    "c",
+    "d",
+    # keep-sorted end
+    "z",
+    "a",
+]
+        "#
+    );
+}
+
+#[test]
+fn bazel_explicit_end_missing() {
+    let result = common::process_input(
+        Strategy::BAZEL,
+        r#"
+block = [
+    # Keep sorted start
+    "b",
+    "a",
+]
+        "#,
+    );
+    let err = result.expect_err("Block without end directive was sorted");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(
+        err.to_string(),
+        "line 3: missing end directive for the block started here"
+    );
+}
+
+#[test]
+fn bazel_crlf() {
+    test_crlf!(
+        Strategy::BAZEL,
+        "block = [\n    # Keep sorted.\n    \"b\",\n    \"a\",  # Comment.\n]\n",
+        "block = [\n    # Keep sorted.\n    \"a\",  # Comment.\n    \"b\",\n]\n"
+    );
+}
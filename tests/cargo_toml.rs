@@ -1,12 +1,13 @@
 #[macro_use]
 mod common;
 
-use keepsorted::Strategy::CargoToml;
+use keepsorted::options::SortOptions;
+use keepsorted::Strategy;
 
 #[test]
 fn cargo_toml_simple() {
     test_inner!(
-        CargoToml,
+        Strategy::CARGO_TOML,
         r#"
 [dependencies]
 b = "2"
@@ -23,7 +24,7 @@ b = "2"
 #[test]
 fn cargo_toml_list_with_item_comment() {
     test_inner!(
-        CargoToml,
+        Strategy::CARGO_TOML,
         r#"
 [dependencies]
 c = "3"
@@ -44,7 +45,7 @@ c = "3"
 #[test]
 fn cargo_toml_list_with_inline_comment() {
     test_inner!(
-        CargoToml,
+        Strategy::CARGO_TOML,
         r#"
 [dependencies]
 c = "3"
@@ -63,7 +64,7 @@ c = "3"
 #[test]
 fn cargo_toml_two_scopes() {
     test_inner!(
-        CargoToml,
+        Strategy::CARGO_TOML,
         r#"
 [dependencies]
 b = "2"
@@ -86,7 +87,7 @@ path = "src/lib.rs"
 #[test]
 fn cargo_toml_block_with_newline_inside() {
     test_inner!(
-        CargoToml,
+        Strategy::CARGO_TOML,
         r#"
 [dependencies]
 b = "2"
@@ -109,7 +110,7 @@ x = "3"
 #[test]
 fn cargo_toml_two_blocks() {
     test_inner!(
-        CargoToml,
+        Strategy::CARGO_TOML,
         r#"
 [dependencies]
 b = "2"
@@ -134,7 +135,7 @@ y = "4"
 #[test]
 fn cargo_toml_ignore_file() {
     test_inner!(
-        CargoToml,
+        Strategy::CARGO_TOML,
         r#"
 # keepsorted: ignore file
 [dependencies]
@@ -161,7 +162,7 @@ x = "3"
 #[test]
 fn cargo_toml_ignore_block_inside() {
     test_inner!(
-        CargoToml,
+        Strategy::CARGO_TOML,
         r#"
 [dependencies]
 # keepsorted: ignore block
@@ -188,7 +189,7 @@ y = "4"
 #[test]
 fn cargo_toml_ignore_block_before() {
     test_inner!(
-        CargoToml,
+        Strategy::CARGO_TOML,
         r#"
 # keepsorted: ignore block
 [dependencies]
@@ -215,7 +216,7 @@ y = "4"
 #[test]
 fn cargo_toml_nested_list() {
     test_inner!(
-        CargoToml,
+        Strategy::CARGO_TOML,
         r#"
 [dependencies]
 b = { workspace = true, default-features = false, features = [
@@ -236,3 +237,128 @@ b = { workspace = true, default-features = false, features = [
         "#
     );
 }
+
+#[test]
+fn cargo_toml_numeric() {
+    test_inner!(
+        Strategy::CARGO_TOML,
+        &SortOptions::default().parse("numeric=yes").unwrap(),
+        r#"
+[dependencies]
+crate10 = "1"
+crate2 = { version = "1", features = [
+    "a",
+] }
+crate1 = "1"
+        "#,
+        r#"
+[dependencies]
+crate1 = "1"
+crate2 = { version = "1", features = [
+    "a",
+] }
+crate10 = "1"
+        "#
+    );
+}
+
+#[test]
+fn cargo_toml_reverse() {
+    test_inner!(
+        Strategy::CARGO_TOML,
+        &SortOptions::default().parse("reverse=yes").unwrap(),
+        r#"
+[dependencies]
+a = "1"
+# Comment about b.
+b = { version = "1", features = [
+    "a",
+] }
+c = "1"
+        "#,
+        r#"
+[dependencies]
+c = "1"
+# Comment about b.
+b = { version = "1", features = [
+    "a",
+] }
+a = "1"
+        "#
+    );
+}
+
+#[test]
+fn cargo_toml_off_on_region() {
+    test_inner!(
+        Strategy::CARGO_TOML,
+        r#"
+[dependencies]
+b = "1"
+a = "1"
+
+# keepsorted: off
+[dev-dependencies]
+d = "1"
+c = "1"
+# keepsorted: on
+
+[build-dependencies]
+f = "1"
+e = "1"
+        "#,
+        r#"
+[dependencies]
+a = "1"
+b = "1"
+
+# keepsorted: off
+[dev-dependencies]
+d = "1"
+c = "1"
+# keepsorted: on
+
+[build-dependencies]
+e = "1"
+f = "1"
+        "#
+    );
+}
+
+#[test]
+fn cargo_toml_off_on_region_in_section() {
+    test_inner!(
+        Strategy::CARGO_TOML,
+        r#"
+[dependencies]
+d = "1"
+c = "1"
+# keepsorted: off
+z = "1"
+y = "1"
+# keepsorted: on
+b = "1"
+a = "1"
+        "#,
+        r#"
+[dependencies]
+c = "1"
+d = "1"
+# keepsorted: off
+z = "1"
+y = "1"
+# keepsorted: on
+a = "1"
+b = "1"
+        "#
+    );
+}
+
+#[test]
+fn cargo_toml_crlf() {
+    test_crlf!(
+        Strategy::CARGO_TOML,
+        "[dependencies]\nb = { version = \"1\", features = [\n    \"x\",\n] }\na = \"1\"\n",
+        "[dependencies]\na = \"1\"\nb = { version = \"1\", features = [\n    \"x\",\n] }\n"
+    );
+}
@@ -0,0 +1,101 @@
+use keepsorted::{classify, Error, Feature, Features, Strategy};
+use std::path::Path;
+
+fn features(names: &[&str]) -> Features {
+    Features::parse(names).expect("Invalid features")
+}
+
+#[test]
+fn classify_bazel_files() {
+    for path in [
+        "BUILD",
+        "WORKSPACE",
+        "BUILD.bazel",
+        "WORKSPACE.bazel",
+        "MODULE.bazel",
+        "path/to/BUILD",
+        "path/to/MODULE.bazel",
+        "defs.bzl",
+        "rules.star",
+        "third_party/zlib.BUILD",
+    ] {
+        assert_eq!(
+            classify(Path::new(path), &Features::default()),
+            Strategy::BAZEL,
+            "Unexpected strategy for {}",
+            path
+        );
+    }
+}
+
+#[test]
+fn classify_other_files() {
+    for (path, features, expected) in [
+        ("Cargo.toml", features(&[]), Strategy::CARGO_TOML),
+        ("BUILD.txt", features(&[]), Strategy::GENERIC),
+        ("BUILDING.md", features(&[]), Strategy::GENERIC),
+        (".gitignore", features(&[]), Strategy::GENERIC),
+        (".gitignore", features(&["gitignore"]), Strategy::GITIGNORE),
+        (
+            ".github/CODEOWNERS",
+            features(&["codeowners"]),
+            Strategy::GITIGNORE,
+        ),
+        ("main.rs", features(&[]), Strategy::GENERIC),
+        (
+            "main.rs",
+            features(&["rust_derive_canonical"]),
+            Strategy::RUST_DERIVE_CANONICAL,
+        ),
+        (
+            "main.rs",
+            features(&["rust_derive_alphabetical"]),
+            Strategy::RUST_DERIVE_ALPHABETICAL,
+        ),
+    ] {
+        assert_eq!(
+            classify(Path::new(path), &features),
+            expected,
+            "Unexpected strategy for {} with {:?}",
+            path,
+            features
+        );
+    }
+}
+
+#[test]
+fn strategies_match_their_file_patterns() {
+    for strategy in Strategy::ALL {
+        for (pattern, feature) in strategy.implementation().file_patterns() {
+            let path = pattern.to_string().replace('*', "file");
+            let features = Features::new(*feature).expect("Invalid features");
+            assert_eq!(
+                classify(Path::new(&path), &features),
+                strategy,
+                "Unexpected strategy for {}",
+                path
+            );
+        }
+    }
+}
+
+#[test]
+fn conflicting_features() {
+    match Features::new([
+        Feature::RustDeriveCanonical,
+        Feature::RustDeriveAlphabetical,
+    ]) {
+        Err(Error::ConflictingFeatures(a, b)) => {
+            assert_eq!(
+                (a, b),
+                (
+                    Feature::RustDeriveAlphabetical,
+                    Feature::RustDeriveCanonical
+                )
+            );
+        }
+        other => panic!("Unexpected result: {:?}", other),
+    }
+    let err = Features::parse(&["gitignore", "unknown"]).expect_err("Unknown feature was accepted");
+    assert!(err.contains("unknown feature 'unknown'"), "{}", err);
+}
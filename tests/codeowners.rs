@@ -1,12 +1,13 @@
 #[macro_use]
 mod common;
 
-use keepsorted::Strategy::Gitignore;
+use keepsorted::options::SortOptions;
+use keepsorted::Strategy;
 
 #[test]
 fn codeowners_simple_block() {
     test_inner!(
-        Gitignore,
+        Strategy::GITIGNORE,
         r#"
 /.d/                 @company/teams/a
 /.c/                 @company/teams/b
@@ -25,7 +26,7 @@ fn codeowners_simple_block() {
 #[test]
 fn codeowners_two_blocks() {
     test_inner!(
-        Gitignore,
+        Strategy::GITIGNORE,
         r#"
 /.d/                 @company/teams/a
 /.c/                 @company/teams/b
@@ -46,7 +47,7 @@ fn codeowners_two_blocks() {
 #[test]
 fn codeowners_ignore_file() {
     test_inner!(
-        Gitignore,
+        Strategy::GITIGNORE,
         r#"
 # keepsorted: ignore file
 /.d/                 @company/teams/a
@@ -69,7 +70,7 @@ fn codeowners_ignore_file() {
 #[test]
 fn codeowners_ignore_block() {
     test_inner!(
-        Gitignore,
+        Strategy::GITIGNORE,
         r#"
 # keepsorted: ignore block
 /.d/                 @company/teams/a
@@ -92,7 +93,7 @@ fn codeowners_ignore_block() {
 #[test]
 fn codeowners_1() {
     test_inner!(
-        Gitignore,
+        Strategy::GITIGNORE,
         r#"
 
 # [Misc]
@@ -123,3 +124,30 @@ fn codeowners_1() {
         "#
     );
 }
+
+#[test]
+fn codeowners_case_insensitive() {
+    test_inner!(
+        Strategy::GITIGNORE,
+        &SortOptions::default().parse("case=insensitive").unwrap(),
+        r#"
+/Zoo @org/team
+/apple @org/team
+/Apple @org/team
+        "#,
+        r#"
+/Apple @org/team
+/apple @org/team
+/Zoo @org/team
+        "#
+    );
+}
+
+#[test]
+fn codeowners_crlf() {
+    test_crlf!(
+        Strategy::GITIGNORE,
+        "# Owners.\n/b @org/b\n/a @org/a\n",
+        "# Owners.\n/a @org/a\n/b @org/b\n"
+    );
+}
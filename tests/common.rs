@@ -1,10 +1,20 @@
-use keepsorted::{process_lines, Strategy};
+use keepsorted::options::SortOptions;
+use keepsorted::{process_lines_with_options, Strategy};
 use std::io::{self};
 
 // Helper function to hide text-lines conversion.
 pub fn process_input(strategy: Strategy, text: &str) -> io::Result<String> {
+    process_input_with_options(strategy, &SortOptions::default(), text)
+}
+
+// Same as `process_input`, but with the default options for every block.
+pub fn process_input_with_options(
+    strategy: Strategy,
+    options: &SortOptions,
+    text: &str,
+) -> io::Result<String> {
     let lines: Vec<_> = text.lines().map(|line| format!("{}\n", line)).collect();
-    let mut processed_lines = process_lines(strategy, lines)?;
+    let mut processed_lines = process_lines_with_options(strategy, lines, options)?;
     if let Some(last) = processed_lines.last_mut() {
         last.truncate(last.trim_end_matches('\n').len());
     }
@@ -26,4 +36,34 @@ macro_rules! test_inner {
             result
         );
     }};
+    ($strategy:expr, $options:expr, $input:expr, $expected:expr) => {{
+        let strategy = $strategy;
+        let options = $options;
+        let input = $input;
+        let expected = $expected;
+        let result = common::process_input_with_options(strategy, options, input).unwrap();
+        assert!(
+            result == expected,
+            "Expected: {}\nActual: {}",
+            expected,
+            result
+        );
+    }};
+}
+
+// Macro for checking that CRLF line endings and the byte order mark are preserved.
+#[macro_export]
+macro_rules! test_crlf {
+    ($strategy:expr, $input:expr, $expected:expr) => {{
+        let input = format!("\u{feff}{}", $input.replace('\n', "\r\n"));
+        let expected = format!("\u{feff}{}", $expected.replace('\n', "\r\n"));
+        let options = keepsorted::options::SortOptions::default();
+        let result = keepsorted::process_text(&input, $strategy, &options).unwrap();
+        assert!(
+            result == expected,
+            "Expected: {:?}\nActual: {:?}",
+            expected,
+            result
+        );
+    }};
 }
@@ -0,0 +1,130 @@
+use keepsorted::config::{Config, CONFIG_FILE_NAME};
+use keepsorted::{Feature, Strategy};
+use std::fs;
+use tempfile::tempdir;
+
+#[test]
+fn config_discovered_from_parent_directory() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    fs::write(
+        temp_dir.path().join(CONFIG_FILE_NAME),
+        r#"
+features = ["gitignore", "rust_derive_canonical"]
+exclude = ["vendor", "generated/**"]
+
+[strategies]
+".dockerignore" = "gitignore"
+"bazel/*.tmpl" = "bazel"
+        "#,
+    )
+    .expect("Failed to write config");
+
+    let config = Config::discover(&temp_dir.path().join("a/b/c.txt"))
+        .expect("Failed to discover config")
+        .expect("Config not found");
+    assert_eq!(
+        config.features().iter().collect::<Vec<_>>(),
+        [Feature::Gitignore, Feature::RustDeriveCanonical]
+    );
+
+    let root = temp_dir.path();
+    assert!(config.is_excluded(&root.join("vendor/a.txt")));
+    assert!(config.is_excluded(&root.join("third_party/vendor/a.txt")));
+    assert!(config.is_excluded(&root.join("generated/x/y.rs")));
+    assert!(!config.is_excluded(&root.join("src/generated.rs")));
+
+    assert_eq!(
+        config.strategy(&root.join("docker/.dockerignore")),
+        Some(Strategy::GITIGNORE)
+    );
+    assert_eq!(
+        config.strategy(&root.join("bazel/deps.bzl.tmpl")),
+        Some(Strategy::BAZEL)
+    );
+    assert_eq!(config.strategy(&root.join("deps.bzl.tmpl")), None);
+}
+
+#[test]
+fn config_closest_file_wins() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    fs::create_dir_all(temp_dir.path().join("nested")).expect("Failed to create directory");
+    fs::write(
+        temp_dir.path().join(CONFIG_FILE_NAME),
+        r#"features = ["gitignore"]"#,
+    )
+    .expect("Failed to write config");
+    fs::write(
+        temp_dir.path().join("nested").join(CONFIG_FILE_NAME),
+        r#"features = ["codeowners"]"#,
+    )
+    .expect("Failed to write config");
+
+    let config = Config::discover(&temp_dir.path().join("nested/CODEOWNERS"))
+        .expect("Failed to discover config")
+        .expect("Config not found");
+    assert_eq!(
+        config.features().iter().collect::<Vec<_>>(),
+        [Feature::Codeowners]
+    );
+}
+
+#[test]
+fn config_strategies_in_file_order() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    fs::write(
+        temp_dir.path().join(CONFIG_FILE_NAME),
+        r#"
+[strategies]
+"bazel/*.tmpl" = "bazel"
+"*.tmpl" = "generic"
+        "#,
+    )
+    .expect("Failed to write config");
+
+    let config =
+        Config::load(&temp_dir.path().join(CONFIG_FILE_NAME)).expect("Failed to load config");
+    let root = temp_dir.path();
+    assert_eq!(
+        config.strategy(&root.join("bazel/deps.bzl.tmpl")),
+        Some(Strategy::BAZEL)
+    );
+    assert_eq!(
+        config.strategy(&root.join("docs/index.md.tmpl")),
+        Some(Strategy::GENERIC)
+    );
+}
+
+#[test]
+fn config_invalid() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    for content in [
+        "unknown_key = true",
+        "features = 1",
+        "features = [\"unknown\"]",
+        "features = [\"rust_derive_alphabetical\", \"rust_derive_canonical\"]",
+        "exclude = [\"[\"]",
+        "[strategies]\n\"*.txt\" = \"unknown\"",
+    ] {
+        let path = temp_dir.path().join(CONFIG_FILE_NAME);
+        fs::write(&path, content).expect("Failed to write config");
+        let err = Config::load(&path).expect_err("Invalid config was loaded");
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData, "{}", content);
+    }
+}
+
+#[test]
+fn config_sort_options() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let path = temp_dir.path().join(CONFIG_FILE_NAME);
+    fs::write(&path, "[sort]\ncase = \"insensitive\"\nreverse = true\n")
+        .expect("Failed to write config");
+    let config = Config::load(&path).expect("Failed to load config");
+    assert!(config.sort_options().case_insensitive);
+    assert!(config.sort_options().reverse);
+
+    fs::write(&path, "[sort]\nunknown = \"yes\"\n").expect("Failed to write config");
+    assert!(
+        Config::load(&path).is_err(),
+        "Unknown sort option was accepted"
+    );
+}
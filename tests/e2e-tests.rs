@@ -1,6 +1,8 @@
 use std::fs;
+use std::io::Write;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
+use std::time::{Duration, SystemTime};
 use tempfile::tempdir;
 
 fn run_test(input_file_path: &str, expected_file_path: &str, features: &str) {
@@ -18,14 +20,8 @@ fn run_test(input_file_path: &str, expected_file_path: &str, features: &str) {
     // Write the input content to a temporary file
     fs::write(&temp_input_file_path, &input_content).expect("Failed to write to temporary file");
 
-    // Determine the path to the keepsorted binary based on the build mode
-    let keepsorted_binary = if cfg!(debug_assertions) {
-        "./target/debug/keepsorted"
-    } else {
-        "./target/release/keepsorted"
-    };
     // Create the command and conditionally add the --features argument if the string is not empty
-    let mut command = Command::new(keepsorted_binary);
+    let mut command = Command::new(keepsorted_binary());
     command.arg(temp_input_file_path.to_str().unwrap());
     if !features.is_empty() {
         command.arg("--features").arg(features);
@@ -56,6 +52,15 @@ fn run_test(input_file_path: &str, expected_file_path: &str, features: &str) {
     );
 }
 
+// Determine the path to the keepsorted binary based on the build mode
+fn keepsorted_binary() -> &'static str {
+    if cfg!(debug_assertions) {
+        "./target/debug/keepsorted"
+    } else {
+        "./target/release/keepsorted"
+    }
+}
+
 fn dir(path: &str) -> String {
     format!("./tests/e2e-tests/{path}")
 }
@@ -85,6 +90,11 @@ fn test_e2e_generic_3() {
     run_test(&dir("generic/3_in.txt"), &dir("generic/3_out.txt"), "");
 }
 
+#[test]
+fn test_e2e_generic_sql_comments() {
+    run_test(&dir("generic/4_in.sql"), &dir("generic/4_out.sql"), "");
+}
+
 #[test]
 fn test_e2e_cargo_toml_1() {
     run_test(
@@ -147,3 +157,616 @@ fn test_e2e_rust_derive_3() {
         "rust_derive_alphabetical",
     );
 }
+
+fn run_check_test(input_file_path: &str, features: &str, expected_lines: &[usize]) {
+    let input_content = fs::read_to_string(input_file_path).expect("Failed to read input file");
+
+    // Create a temporary directory
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let temp_input_file_path = temp_dir
+        .path()
+        .join(Path::new(input_file_path).file_name().unwrap());
+    fs::write(&temp_input_file_path, &input_content).expect("Failed to write to temporary file");
+
+    let mut command = Command::new(keepsorted_binary());
+    command
+        .arg(temp_input_file_path.to_str().unwrap())
+        .arg("--check");
+    if !features.is_empty() {
+        command.arg("--features").arg(features);
+    }
+    let output = command.output().expect("Failed to execute keepsorted");
+
+    // The command fails if and only if some block is not sorted.
+    assert_eq!(
+        output.status.success(),
+        expected_lines.is_empty(),
+        "Unexpected keepsorted --check exit status"
+    );
+
+    let expected_stdout: String = expected_lines
+        .iter()
+        .map(|line| format!("{}:{}\n", temp_input_file_path.display(), line))
+        .collect();
+    assert_eq!(String::from_utf8_lossy(&output.stdout), expected_stdout);
+
+    // Ensure the file is not modified in check mode
+    let output_content =
+        fs::read_to_string(&temp_input_file_path).expect("Failed to read output file");
+    assert_eq!(
+        input_content, output_content,
+        "The file content was modified in check mode"
+    );
+}
+
+#[test]
+fn test_e2e_sorted_file_is_not_written() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let temp_file_path = temp_dir.path().join("3_out.txt");
+    fs::copy(dir("generic/3_out.txt"), &temp_file_path).expect("Failed to copy input file");
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    fs::File::options()
+        .write(true)
+        .open(&temp_file_path)
+        .and_then(|file| file.set_modified(modified))
+        .expect("Failed to set the modification time");
+
+    let output = Command::new(keepsorted_binary())
+        .arg(&temp_file_path)
+        .output()
+        .expect("Failed to execute keepsorted");
+    assert!(output.status.success(), "keepsorted command failed");
+
+    let metadata = fs::metadata(&temp_file_path).expect("Failed to read metadata");
+    assert_eq!(metadata.modified().unwrap(), modified);
+}
+
+#[test]
+fn test_e2e_mixed_line_endings_are_not_rewritten() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let temp_file_path = temp_dir.path().join("mixed.txt");
+    let content = "x\r\ny\n# Keep sorted\r\na\nb\r\n";
+    fs::write(&temp_file_path, content).expect("Failed to write input file");
+    let modified = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+    fs::File::options()
+        .write(true)
+        .open(&temp_file_path)
+        .and_then(|file| file.set_modified(modified))
+        .expect("Failed to set the modification time");
+
+    for args in [&[][..], &["--check"], &["--diff"]] {
+        let output = Command::new(keepsorted_binary())
+            .arg(&temp_file_path)
+            .args(args)
+            .output()
+            .expect("Failed to execute keepsorted");
+        assert!(output.status.success(), "keepsorted {:?} failed", args);
+        assert!(
+            output.stdout.is_empty(),
+            "keepsorted {:?} printed output",
+            args
+        );
+    }
+
+    let metadata = fs::metadata(&temp_file_path).expect("Failed to read metadata");
+    assert_eq!(metadata.modified().unwrap(), modified);
+    assert_eq!(fs::read_to_string(&temp_file_path).unwrap(), content);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_e2e_write_keeps_permissions_and_symlinks() {
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let temp_file_path = temp_dir.path().join("3.txt");
+    let link_path = temp_dir.path().join("link.txt");
+    fs::copy(dir("generic/3_in.txt"), &temp_file_path).expect("Failed to copy input file");
+    fs::set_permissions(&temp_file_path, fs::Permissions::from_mode(0o750))
+        .expect("Failed to set permissions");
+    symlink(&temp_file_path, &link_path).expect("Failed to create symlink");
+
+    let output = Command::new(keepsorted_binary())
+        .arg(&link_path)
+        .output()
+        .expect("Failed to execute keepsorted");
+    assert!(output.status.success(), "keepsorted command failed");
+
+    let expected_content =
+        fs::read_to_string(dir("generic/3_out.txt")).expect("Failed to read expected file");
+    let output_content = fs::read_to_string(&temp_file_path).expect("Failed to read output file");
+    assert_eq!(output_content, expected_content);
+    let metadata = fs::metadata(&temp_file_path).expect("Failed to read metadata");
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o750);
+    assert!(fs::symlink_metadata(&link_path)
+        .expect("Failed to read metadata")
+        .file_type()
+        .is_symlink());
+    assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+}
+
+#[test]
+fn test_e2e_check_generic_unsorted() {
+    run_check_test(&dir("generic/3_in.txt"), "", &[8]);
+}
+
+#[test]
+fn test_e2e_check_generic_sorted() {
+    run_check_test(&dir("generic/3_out.txt"), "", &[]);
+}
+
+#[test]
+fn test_e2e_check_bazel_unsorted() {
+    run_check_test(&dir("bazel/1_in.bazel"), "", &[8, 17, 29]);
+}
+
+#[test]
+fn test_e2e_check_rust_derive_sorted() {
+    run_check_test(&dir("rust_derive/2_out.rs"), "rust_derive_canonical", &[]);
+}
+
+#[test]
+fn test_e2e_check_duplicates() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let temp_input_file_path = temp_dir.path().join("duplicates.txt");
+    let input_content = "# Keep sorted unique=yes\na\nb\na\n\n# Keep sorted\nb\na\n";
+    fs::write(&temp_input_file_path, input_content).expect("Failed to write to temporary file");
+
+    let output = Command::new(keepsorted_binary())
+        .arg(temp_input_file_path.to_str().unwrap())
+        .arg("--check")
+        .output()
+        .expect("Failed to execute keepsorted");
+    assert!(!output.status.success(), "keepsorted --check succeeded");
+
+    let name = temp_input_file_path.display();
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!("{name}:2: duplicate items\n{name}:7\n")
+    );
+
+    // Ensure the duplicates are not removed in check mode
+    let output_content =
+        fs::read_to_string(&temp_input_file_path).expect("Failed to read output file");
+    assert_eq!(input_content, output_content);
+}
+
+#[test]
+fn test_e2e_diff_generic() {
+    let input_content =
+        fs::read_to_string(dir("generic/3_in.txt")).expect("Failed to read input file");
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let temp_input_file_path = temp_dir.path().join("3_in.txt");
+    fs::write(&temp_input_file_path, &input_content).expect("Failed to write to temporary file");
+
+    let output = Command::new(keepsorted_binary())
+        .arg(temp_input_file_path.to_str().unwrap())
+        .arg("--diff")
+        .output()
+        .expect("Failed to execute keepsorted");
+
+    // The command fails because the diff is not empty.
+    assert!(!output.status.success(), "keepsorted --diff succeeded");
+
+    let name = temp_input_file_path.display();
+    let expected_stdout = format!(
+        "--- {name}\n+++ {name}\n@@ -5,8 +5,8 @@\n \n # Block #2.\n # Keep sorted.\n+a\n b\n-a\n \n # Block #3.\n b\n"
+    );
+    assert_eq!(String::from_utf8_lossy(&output.stdout), expected_stdout);
+
+    // Ensure the file is not modified in diff mode
+    let output_content =
+        fs::read_to_string(&temp_input_file_path).expect("Failed to read output file");
+    assert_eq!(
+        input_content, output_content,
+        "The file content was modified in diff mode"
+    );
+}
+
+#[test]
+fn test_e2e_directory() {
+    // Create a temporary directory with nested inputs
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let cases = [
+        ("generic/3_in.txt", "generic/3_out.txt", "3.txt"),
+        ("bazel/1_in.bazel", "bazel/1_out.bazel", "nested/1.bazel"),
+        (
+            "bazel/2_in.bazel",
+            "bazel/2_out.bazel",
+            "nested/deeper/2.bazel",
+        ),
+    ];
+    for (input_file_path, _, name) in cases {
+        let temp_file_path = temp_dir.path().join(name);
+        fs::create_dir_all(temp_file_path.parent().unwrap())
+            .expect("Failed to create temporary directory");
+        fs::copy(dir(input_file_path), &temp_file_path).expect("Failed to copy input file");
+    }
+
+    let output = Command::new(keepsorted_binary())
+        .arg(temp_dir.path().to_str().unwrap())
+        .output()
+        .expect("Failed to execute keepsorted");
+    assert!(output.status.success(), "keepsorted command failed");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).ends_with(
+            "keepsorted: 3 files sorted, 0 files left unchanged, 0 files skipped, 0 files failed\n"
+        ),
+        "Unexpected summary: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    for (_, expected_file_path, name) in cases {
+        let expected_content =
+            fs::read_to_string(dir(expected_file_path)).expect("Failed to read expected file");
+        let output_content =
+            fs::read_to_string(temp_dir.path().join(name)).expect("Failed to read output file");
+        assert_eq!(
+            output_content, expected_content,
+            "The output content of {} does not match the expected content",
+            name
+        );
+    }
+}
+
+#[test]
+fn test_e2e_directory_skips_binary_files() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    fs::copy(dir("generic/3_in.txt"), temp_dir.path().join("3.txt"))
+        .expect("Failed to copy input file");
+    fs::write(temp_dir.path().join("image.bin"), b"\xff\xfe\x00\x01\n")
+        .expect("Failed to write binary file");
+
+    let output = Command::new(keepsorted_binary())
+        .arg(temp_dir.path().to_str().unwrap())
+        .output()
+        .expect("Failed to execute keepsorted");
+    assert!(output.status.success(), "keepsorted command failed");
+    assert!(
+        String::from_utf8_lossy(&output.stderr).ends_with(
+            "keepsorted: 1 file sorted, 0 files left unchanged, 1 file skipped, 0 files failed\n"
+        ),
+        "Unexpected summary: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_e2e_directory_unreadable_entry() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let unreadable_dir = temp_dir.path().join("b");
+    fs::create_dir(&unreadable_dir).expect("Failed to create directory");
+    for name in ["a/3.txt", "c/3.txt"] {
+        let temp_file_path = temp_dir.path().join(name);
+        fs::create_dir_all(temp_file_path.parent().unwrap())
+            .expect("Failed to create temporary directory");
+        fs::copy(dir("generic/3_in.txt"), &temp_file_path).expect("Failed to copy input file");
+    }
+    fs::set_permissions(&unreadable_dir, fs::Permissions::from_mode(0o000))
+        .expect("Failed to set permissions");
+    if fs::read_dir(&unreadable_dir).is_ok() {
+        // Permissions are not enforced, eg. when running as root.
+        fs::set_permissions(&unreadable_dir, fs::Permissions::from_mode(0o755))
+            .expect("Failed to set permissions");
+        return;
+    }
+
+    let output = Command::new(keepsorted_binary())
+        .arg(temp_dir.path().to_str().unwrap())
+        .output()
+        .expect("Failed to execute keepsorted");
+    fs::set_permissions(&unreadable_dir, fs::Permissions::from_mode(0o755))
+        .expect("Failed to set permissions");
+    assert!(!output.status.success(), "keepsorted command succeeded");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        stderr.ends_with(
+            "keepsorted: 2 files sorted, 0 files left unchanged, 0 files skipped, 1 file failed\n"
+        ),
+        "Unexpected summary: {}",
+        stderr
+    );
+
+    let expected_content =
+        fs::read_to_string(dir("generic/3_out.txt")).expect("Failed to read expected file");
+    for name in ["a/3.txt", "c/3.txt"] {
+        let output_content =
+            fs::read_to_string(temp_dir.path().join(name)).expect("Failed to read output file");
+        assert_eq!(output_content, expected_content, "{} was not sorted", name);
+    }
+}
+
+#[test]
+fn test_e2e_check_multiple_paths() {
+    let output = Command::new(keepsorted_binary())
+        .arg(dir("generic/3_in.txt"))
+        .arg(dir("generic/3_out.txt"))
+        .arg("--check")
+        .output()
+        .expect("Failed to execute keepsorted");
+    assert!(!output.status.success(), "keepsorted --check succeeded");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        format!("{}:8\n", dir("generic/3_in.txt"))
+    );
+}
+
+#[test]
+fn test_e2e_directory_ignored_and_excluded() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let input_content =
+        fs::read_to_string(dir("generic/3_in.txt")).expect("Failed to read input file");
+    let expected_content =
+        fs::read_to_string(dir("generic/3_out.txt")).expect("Failed to read expected file");
+    for name in [
+        "src/a.txt",
+        "src/b.md",
+        "target/a.txt",
+        "third_party/vendor/a.txt",
+        "ignored.txt",
+    ] {
+        let temp_file_path = temp_dir.path().join(name);
+        fs::create_dir_all(temp_file_path.parent().unwrap())
+            .expect("Failed to create temporary directory");
+        fs::write(&temp_file_path, &input_content).expect("Failed to write to temporary file");
+    }
+    fs::write(temp_dir.path().join(".gitignore"), "target/\n").expect("Failed to write .gitignore");
+    fs::write(temp_dir.path().join(".ignore"), "ignored.txt\n").expect("Failed to write .ignore");
+
+    let output = Command::new(keepsorted_binary())
+        .arg(temp_dir.path().to_str().unwrap())
+        .args(["--exclude", "vendor", "--include", "*.txt"])
+        .output()
+        .expect("Failed to execute keepsorted");
+    assert!(output.status.success(), "keepsorted command failed");
+
+    // Only `src/a.txt` is neither ignored, excluded nor filtered out by the include glob.
+    for (name, expected) in [
+        ("src/a.txt", &expected_content),
+        ("src/b.md", &input_content),
+        ("target/a.txt", &input_content),
+        ("third_party/vendor/a.txt", &input_content),
+        ("ignored.txt", &input_content),
+    ] {
+        let output_content =
+            fs::read_to_string(temp_dir.path().join(name)).expect("Failed to read output file");
+        assert_eq!(&output_content, expected, "Unexpected content of {}", name);
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_e2e_directory_excluded_by_config_is_not_walked() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let input_content =
+        fs::read_to_string(dir("generic/3_in.txt")).expect("Failed to read input file");
+    for name in ["src/a.txt", "third_party/a.txt"] {
+        let temp_file_path = temp_dir.path().join(name);
+        fs::create_dir_all(temp_file_path.parent().unwrap())
+            .expect("Failed to create temporary directory");
+        fs::write(&temp_file_path, &input_content).expect("Failed to write to temporary file");
+    }
+    fs::write(
+        temp_dir.path().join("keepsorted.toml"),
+        "exclude = [\"third_party\"]\n",
+    )
+    .expect("Failed to write config");
+    let unreadable_dir = temp_dir.path().join("third_party/locked");
+    fs::create_dir(&unreadable_dir).expect("Failed to create directory");
+    fs::set_permissions(&unreadable_dir, fs::Permissions::from_mode(0o000))
+        .expect("Failed to set permissions");
+
+    let output = Command::new(keepsorted_binary())
+        .arg(temp_dir.path().to_str().unwrap())
+        .output()
+        .expect("Failed to execute keepsorted");
+    fs::set_permissions(&unreadable_dir, fs::Permissions::from_mode(0o755))
+        .expect("Failed to set permissions");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        output.status.success(),
+        "keepsorted command failed: {}",
+        stderr
+    );
+    assert!(
+        stderr.ends_with(
+            "keepsorted: 1 file sorted, 1 file left unchanged, 0 files skipped, 0 files failed\n"
+        ),
+        "Unexpected summary: {}",
+        stderr
+    );
+    let output_content = fs::read_to_string(temp_dir.path().join("third_party/a.txt"))
+        .expect("Failed to read output file");
+    assert_eq!(output_content, input_content);
+}
+
+fn run_stdin_test(input_file_path: &str, expected_file_path: &str, stdin_filename: &str) {
+    let input_content = fs::read_to_string(input_file_path).expect("Failed to read input file");
+    let expected_content = fs::read_to_string(expected_file_path)
+        .unwrap_or_else(|_| panic!("Failed to read expected file: {}", expected_file_path));
+
+    let mut child = Command::new(keepsorted_binary())
+        .arg("-")
+        .arg("--stdin-filename")
+        .arg(stdin_filename)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .expect("Failed to execute keepsorted");
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(input_content.as_bytes())
+        .expect("Failed to write to stdin");
+    let output = child
+        .wait_with_output()
+        .expect("Failed to wait for keepsorted");
+
+    assert!(output.status.success(), "keepsorted command failed");
+    assert_eq!(
+        String::from_utf8_lossy(&output.stdout),
+        expected_content,
+        "The output content does not match the expected content"
+    );
+}
+
+#[test]
+fn test_e2e_stdin_generic() {
+    run_stdin_test(&dir("generic/3_in.txt"), &dir("generic/3_out.txt"), "");
+}
+
+#[test]
+fn test_e2e_stdin_bazel() {
+    run_stdin_test(
+        &dir("bazel/1_in.bazel"),
+        &dir("bazel/1_out.bazel"),
+        "BUILD.bazel",
+    );
+}
+
+#[test]
+fn test_e2e_stdin_cargo_toml() {
+    run_stdin_test(
+        &dir("cargo_toml/1/Cargo.toml"),
+        &dir("cargo_toml/1/Cargo_out.toml"),
+        "path/to/Cargo.toml",
+    );
+}
+
+#[test]
+fn test_e2e_strategy_override() {
+    let input_content =
+        fs::read_to_string(dir("bazel/1_in.bazel")).expect("Failed to read input file");
+    let expected_content =
+        fs::read_to_string(dir("bazel/1_out.bazel")).expect("Failed to read expected file");
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    let temp_input_file_path = temp_dir.path().join("deps.bzl.tmpl");
+    fs::write(&temp_input_file_path, &input_content).expect("Failed to write to temporary file");
+
+    let output = Command::new(keepsorted_binary())
+        .arg(temp_input_file_path.to_str().unwrap())
+        .arg("--strategy")
+        .arg("bazel")
+        .output()
+        .expect("Failed to execute keepsorted");
+    assert!(output.status.success(), "keepsorted command failed");
+
+    let output_content =
+        fs::read_to_string(&temp_input_file_path).expect("Failed to read output file");
+    assert_eq!(output_content, expected_content);
+}
+
+#[test]
+fn test_e2e_unknown_strategy() {
+    let output = Command::new(keepsorted_binary())
+        .arg(dir("generic/3_in.txt"))
+        .arg("--strategy")
+        .arg("unknown")
+        .output()
+        .expect("Failed to execute keepsorted");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(
+        String::from_utf8_lossy(&output.stderr).contains("unknown strategy 'unknown'"),
+        "Unexpected error: {}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
+
+#[test]
+fn test_e2e_invalid_features() {
+    for (features, expected) in [
+        ("gitignore,unknown", "unknown feature 'unknown'"),
+        (
+            "rust_derive_alphabetical,rust_derive_canonical",
+            "features 'rust_derive_alphabetical' and 'rust_derive_canonical' cannot be enabled together",
+        ),
+    ] {
+        let output = Command::new(keepsorted_binary())
+            .arg(dir("rust_derive/1_in.rs"))
+            .arg("--features")
+            .arg(features)
+            .output()
+            .expect("Failed to execute keepsorted");
+        assert_eq!(output.status.code(), Some(2), "{}", features);
+        assert!(
+            String::from_utf8_lossy(&output.stderr).contains(expected),
+            "Unexpected error: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+}
+
+#[test]
+fn test_e2e_list_strategies() {
+    let output = Command::new(keepsorted_binary())
+        .arg("--list-strategies")
+        .output()
+        .expect("Failed to execute keepsorted");
+    assert!(output.status.success(), "keepsorted command failed");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    for name in [
+        "generic",
+        "bazel",
+        "cargo_toml",
+        "gitignore",
+        "rust_derive_alphabetical",
+        "rust_derive_canonical",
+    ] {
+        assert!(
+            stdout.lines().any(|line| line == name),
+            "Strategy {} is not listed: {}",
+            name,
+            stdout
+        );
+    }
+}
+
+#[test]
+fn test_e2e_config() {
+    let temp_dir = tempdir().expect("Failed to create temporary directory");
+    fs::write(
+        temp_dir.path().join("keepsorted.toml"),
+        "features = [\"gitignore\"]\nexclude = [\"vendor\"]\n\n[strategies]\n\".dockerignore\" = \"gitignore\"\n",
+    )
+    .expect("Failed to write config");
+    for name in [".gitignore", "docker/.dockerignore", "vendor/.gitignore"] {
+        let temp_file_path = temp_dir.path().join(name);
+        fs::create_dir_all(temp_file_path.parent().unwrap())
+            .expect("Failed to create temporary directory");
+        fs::write(&temp_file_path, "b\na\n").expect("Failed to write to temporary file");
+    }
+
+    let output = Command::new(keepsorted_binary())
+        .arg(temp_dir.path().to_str().unwrap())
+        .output()
+        .expect("Failed to execute keepsorted");
+    assert!(output.status.success(), "keepsorted command failed");
+
+    for (name, expected) in [
+        (".gitignore", "a\nb\n"),
+        ("docker/.dockerignore", "a\nb\n"),
+        ("vendor/.gitignore", "b\na\n"),
+    ] {
+        let output_content =
+            fs::read_to_string(temp_dir.path().join(name)).expect("Failed to read output file");
+        assert_eq!(output_content, expected, "Unexpected content of {}", name);
+    }
+
+    // Feature flags from the command line override the ones from the config.
+    fs::write(temp_dir.path().join(".gitignore"), "b\na\n").expect("Failed to write .gitignore");
+    let output = Command::new(keepsorted_binary())
+        .arg(temp_dir.path().join(".gitignore").to_str().unwrap())
+        .args(["--features", "codeowners"])
+        .output()
+        .expect("Failed to execute keepsorted");
+    assert!(output.status.success(), "keepsorted command failed");
+    let output_content =
+        fs::read_to_string(temp_dir.path().join(".gitignore")).expect("Failed to read output file");
+    assert_eq!(output_content, "b\na\n");
+}
@@ -0,0 +1,12 @@
+-- Expect only the block with "Keep sorted" comment to be sorted.
+CREATE TABLE users (
+  -- Keep sorted
+  email TEXT,
+  -- Primary key.
+  id INT,
+  name TEXT,
+
+  # Not a comment in SQL.
+  b INT,
+  a INT
+);
@@ -1,12 +1,16 @@
 #[macro_use]
 mod common;
 
-use keepsorted::Strategy::Generic;
+use keepsorted::check_lines;
+use keepsorted::comments::CommentSyntax;
+use keepsorted::options::SortOptions;
+use keepsorted::Strategy;
+use std::path::Path;
 
 #[test]
 fn generic_simple_block() {
     test_inner!(
-        Generic,
+        Strategy::GENERIC,
         r#"
 # Keep sorted.
 b
@@ -23,7 +27,7 @@ b
 #[test]
 fn generic_simple_block_2() {
     test_inner!(
-        Generic,
+        Strategy::GENERIC,
         r#"
 // Keep sorted.
 b
@@ -40,7 +44,7 @@ b
 #[test]
 fn generic_simple_block_3() {
     test_inner!(
-        Generic,
+        Strategy::GENERIC,
         r#"
 # keepsorted: keep sorted
 b
@@ -57,7 +61,7 @@ b
 #[test]
 fn generic_blocks_with_newline() {
     test_inner!(
-        Generic,
+        Strategy::GENERIC,
         r#"
 # Keep sorted.
 y
@@ -80,7 +84,7 @@ a
 #[test]
 fn generic_several_blocks() {
     test_inner!(
-        Generic,
+        Strategy::GENERIC,
         r#"
 # Keep sorted.
 y
@@ -105,7 +109,7 @@ b
 #[test]
 fn generic_block_with_multi_line_comment() {
     test_inner!(
-        Generic,
+        Strategy::GENERIC,
         r#"
 # Keep sorted.
 y
@@ -130,7 +134,7 @@ y
 #[test]
 fn generic_block_with_trailing_comment() {
     test_inner!(
-        Generic,
+        Strategy::GENERIC,
         r#"
 # Keep sorted.
 b
@@ -151,7 +155,7 @@ b
 #[test]
 fn generic_block_with_inline_comment() {
     test_inner!(
-        Generic,
+        Strategy::GENERIC,
         r#"
 # Keep sorted.
 y
@@ -172,7 +176,7 @@ y
 #[test]
 fn generic_ignore_file() {
     test_inner!(
-        Generic,
+        Strategy::GENERIC,
         r#"
   # keepsorted: ignore file
 # Keep sorted.
@@ -207,7 +211,7 @@ fn generic_ignore_file() {
 #[test]
 fn generic_ignore_block_inside() {
     test_inner!(
-        Generic,
+        Strategy::GENERIC,
         r#"
 # Keep sorted.
 1b
@@ -242,7 +246,7 @@ fn generic_ignore_block_inside() {
 #[test]
 fn generic_ignore_block_before() {
     test_inner!(
-        Generic,
+        Strategy::GENERIC,
         r#"
 # Keep sorted.
 1b
@@ -279,7 +283,7 @@ fn generic_ignore_block_before() {
 #[ignore]
 fn with_multi_line_comment_rust() {
     test_inner!(
-        Generic,
+        Strategy::GENERIC,
         r#"
 // Keep sorted.
 y,
@@ -304,3 +308,853 @@ y,
         "#
     );
 }
+
+#[test]
+fn generic_explicit_end() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted start
+b
+a
+# keepsorted: end
+d
+c
+        "#,
+        r#"
+# Keep sorted start
+a
+b
+# keepsorted: end
+d
+c
+        "#
+    );
+}
+
+#[test]
+fn generic_explicit_end_with_groups() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+// keepsorted: keep sorted start
+b
+a
+
+d
+// Comment for c.
+c
+// keepsorted: end
+
+y
+x
+        "#,
+        r#"
+// keepsorted: keep sorted start
+a
+b
+
+// Comment for c.
+c
+d
+// keepsorted: end
+
+y
+x
+        "#
+    );
+}
+
+#[test]
+fn generic_explicit_end_ignore_block() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted start
+b
+a
+
+# keepsorted: ignore block
+d
+c
+# Keep sorted end
+        "#,
+        r#"
+# Keep sorted start
+b
+a
+
+# keepsorted: ignore block
+d
+c
+# Keep sorted end
+        "#
+    );
+}
+
+#[test]
+fn generic_explicit_end_missing() {
+    let result = common::process_input(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted start
+b
+a
+
+d
+c
+        "#,
+    );
+    let err = result.expect_err("Block without end directive was sorted");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(
+        err.to_string(),
+        "line 2: missing end directive for the block started here"
+    );
+}
+
+#[test]
+fn generic_end_directive_closes_block() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted
+d
+c
+# keepsorted: end
+b
+a
+        "#,
+        r#"
+# Keep sorted
+c
+d
+# keepsorted: end
+b
+a
+        "#
+    );
+}
+
+#[test]
+fn generic_google_keep_sorted_markers() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+// keep-sorted start
+b
+a
+
+d
+c
+// keep-sorted end
+z
+y
+        "#,
+        r#"
+// keep-sorted start
+a
+b
+
+c
+d
+// keep-sorted end
+z
+y
+        "#
+    );
+}
+
+#[test]
+fn generic_google_keep_sorted_groups_indented_lines() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+// keep-sorted start
+- b
+  x
+- a
+  y
+// keep-sorted end
+        "#,
+        r#"
+// keep-sorted start
+- a
+  y
+- b
+  x
+// keep-sorted end
+        "#
+    );
+}
+
+#[test]
+fn generic_google_keep_sorted_group_and_remove_duplicates_options() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+// keep-sorted start group=no remove_duplicates=yes
+- b
+  x
+- a
+- a
+// keep-sorted end
+        "#,
+        r#"
+// keep-sorted start group=no remove_duplicates=yes
+  x
+- a
+- b
+// keep-sorted end
+        "#
+    );
+}
+
+#[test]
+fn generic_google_keep_sorted_skip_lines() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+# keep-sorted start skip_lines=2 newline_separated=no
+| name | value |
+|------|-------|
+| b    | 2     |
+| a    | 1     |
+# keep-sorted end
+        "#,
+        r#"
+# keep-sorted start skip_lines=2 newline_separated=no
+| name | value |
+|------|-------|
+| a    | 1     |
+| b    | 2     |
+# keep-sorted end
+        "#
+    );
+}
+
+#[test]
+fn generic_skip_lines_option() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+# keepsorted: keep sorted skip_lines=1
+header
+b
+a
+        "#,
+        r#"
+# keepsorted: keep sorted skip_lines=1
+header
+a
+b
+        "#
+    );
+}
+
+#[test]
+fn generic_invalid_option() {
+    let result = common::process_input(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted skip_lines=many
+b
+a
+        "#,
+    );
+    let err = result.expect_err("Invalid option was accepted");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(
+        err.to_string(),
+        "line 2: invalid value 'many' for option 'skip_lines', expected a number"
+    );
+}
+
+#[test]
+fn generic_unknown_option() {
+    let result = common::process_input(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted skip_line=1
+b
+a
+        "#,
+    );
+    let err = result.expect_err("Unknown option was accepted");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(err.to_string(), "line 2: unknown option 'skip_line'");
+}
+
+#[test]
+fn generic_case_insensitive_option() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+# keepsorted: keep sorted case=insensitive
+Zebra
+apple
+Banana
+banana
+        "#,
+        r#"
+# keepsorted: keep sorted case=insensitive
+apple
+Banana
+banana
+Zebra
+        "#
+    );
+}
+
+#[test]
+fn generic_case_insensitive_default() {
+    test_inner!(
+        Strategy::GENERIC,
+        &SortOptions::default().parse("case=insensitive").unwrap(),
+        r#"
+# Keep sorted
+Zebra
+apple
+
+# Keep sorted case=sensitive
+Zebra
+apple
+        "#,
+        r#"
+# Keep sorted
+apple
+Zebra
+
+# Keep sorted case=sensitive
+Zebra
+apple
+        "#
+    );
+}
+
+#[test]
+fn generic_numeric_option() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted numeric=yes
+file10
+file2
+v1.10
+v1.9
+file1
+        "#,
+        r#"
+# Keep sorted numeric=yes
+file1
+file2
+file10
+v1.9
+v1.10
+        "#
+    );
+}
+
+#[test]
+fn generic_google_numeric_and_case_options() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+// keep-sorted start numeric=yes case=no
+Item10
+item9
+// keep-sorted end
+        "#,
+        r#"
+// keep-sorted start numeric=yes case=no
+item9
+Item10
+// keep-sorted end
+        "#
+    );
+}
+
+#[test]
+fn generic_unique_option() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted unique=yes
+b
+# Comment about a.
+a
+b
+# Other comment about a.
+a
+# Comment about a.
+a
+        "#,
+        r#"
+# Keep sorted unique=yes
+# Comment about a.
+# Other comment about a.
+a
+b
+        "#
+    );
+}
+
+#[test]
+fn generic_reverse_option() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted reverse=yes numeric=yes
+v1.9
+# Latest release.
+v1.10
+v0.1
+# Trailing comment.
+        "#,
+        r#"
+# Keep sorted reverse=yes numeric=yes
+# Latest release.
+v1.10
+v1.9
+v0.1
+# Trailing comment.
+        "#
+    );
+}
+
+#[test]
+fn generic_by_regex_option() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted by_regex=^(\w+)=
+FOO_BAR=1
+FOO=2
+# Comment about BAZ.
+BAZ=3
+        "#,
+        r#"
+# Keep sorted by_regex=^(\w+)=
+# Comment about BAZ.
+BAZ=3
+FOO=2
+FOO_BAR=1
+        "#
+    );
+}
+
+#[test]
+fn generic_by_regex_option_table_column() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+// keep-sorted start skip_lines=2 by_regex=^\|[^|]*\|\s*([^|]*?)\s*\|
+| Name  | Age |
+|-------|-----|
+| Bob   | 42  |
+| Alice | 7   |
+| Carol | 42  |
+| Dave  | 13  |
+// keep-sorted end
+        "#,
+        r#"
+// keep-sorted start skip_lines=2 by_regex=^\|[^|]*\|\s*([^|]*?)\s*\|
+| Name  | Age |
+|-------|-----|
+| Dave  | 13  |
+| Bob   | 42  |
+| Carol | 42  |
+| Alice | 7   |
+// keep-sorted end
+        "#
+    );
+}
+
+#[test]
+fn generic_invalid_by_regex_option() {
+    let result = common::process_input(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted by_regex=(
+b
+a
+        "#,
+    );
+    let err = result.expect_err("Invalid regex was accepted");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(
+        err.to_string(),
+        "line 2: invalid value '(' for option 'by_regex', expected a regex"
+    );
+}
+
+#[test]
+fn generic_group_by_indent_option() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted group=indent
+- name: b
+  # Comment inside b.
+  value: 1
+- name: a
+  value: 2
+# Comment about c.
+- name: c
+  nested:
+    - z
+    - y
+        "#,
+        r#"
+# Keep sorted group=indent
+- name: a
+  value: 2
+- name: b
+  # Comment inside b.
+  value: 1
+# Comment about c.
+- name: c
+  nested:
+    - z
+    - y
+        "#
+    );
+}
+
+#[test]
+fn generic_group_by_indent_option_indented_list() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+  // keep-sorted start group=indent
+  * Zebra
+    with a continuation line.
+  * Apple
+  // keep-sorted end
+        "#,
+        r#"
+  // keep-sorted start group=indent
+  * Apple
+  * Zebra
+    with a continuation line.
+  // keep-sorted end
+        "#
+    );
+}
+
+#[test]
+fn generic_groups_option() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted groups=2
+import sys
+import os
+
+from b import y
+from a import x
+
+zebra
+apple
+        "#,
+        r#"
+# Keep sorted groups=2
+import os
+import sys
+
+from a import x
+from b import y
+
+zebra
+apple
+        "#
+    );
+}
+
+#[test]
+fn generic_until_option() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted until=^#\s*End
+b
+a
+
+
+d
+c
+
+# End of imports.
+y
+x
+        "#,
+        r#"
+# Keep sorted until=^#\s*End
+a
+b
+
+
+c
+d
+
+# End of imports.
+y
+x
+        "#
+    );
+}
+
+#[test]
+fn generic_until_option_without_match() {
+    let result = common::process_input(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted until=^#\s*End
+b
+a
+
+d
+c
+        "#,
+    );
+    let err = result.expect_err("Block without a line matching 'until' was sorted");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(
+        err.to_string(),
+        r"line 2: no line matches the 'until' regex '^#\s*End' after the block started here"
+    );
+}
+
+fn options_for(path: &str) -> SortOptions {
+    let mut options = SortOptions::default();
+    options.comments = CommentSyntax::for_path(Path::new(path));
+    options
+}
+
+#[test]
+fn generic_sql_comments() {
+    test_inner!(
+        Strategy::GENERIC,
+        &options_for("schema.sql"),
+        r#"
+-- Keep sorted
+-- Comment about b.
+b INT,
+a INT,
+
+/* keepsorted: ignore block */
+-- Keep sorted
+d INT,
+c INT,
+        "#,
+        r#"
+-- Keep sorted
+a INT,
+-- Comment about b.
+b INT,
+
+/* keepsorted: ignore block */
+-- Keep sorted
+d INT,
+c INT,
+        "#
+    );
+}
+
+#[test]
+fn generic_rust_attributes() {
+    test_inner!(
+        Strategy::GENERIC,
+        &options_for("lib.rs"),
+        r#"
+// Keep sorted
+#[cfg(test)]
+mod b;
+// Comment about a.
+#![allow(dead_code)]
+mod a;
+        "#,
+        r#"
+// Keep sorted
+// Comment about a.
+#![allow(dead_code)]
+mod a;
+#[cfg(test)]
+mod b;
+        "#
+    );
+}
+
+#[test]
+fn generic_html_comments() {
+    test_inner!(
+        Strategy::GENERIC,
+        &options_for("README.md"),
+        r#"
+<!-- keep-sorted start -->
+# Not a comment
+- b
+<!-- Comment about a. -->
+- a
+<!-- keep-sorted end -->
+        "#,
+        r#"
+<!-- keep-sorted start -->
+# Not a comment
+<!-- Comment about a. -->
+- a
+- b
+<!-- keep-sorted end -->
+        "#
+    );
+}
+
+#[test]
+fn generic_other_comments() {
+    for (path, comment) in [
+        ("a.lua", "--"),
+        ("a.lisp", ";;"),
+        ("a.ini", ";"),
+        ("a.tex", "%"),
+        ("a.bat", "REM"),
+    ] {
+        let input = format!("\n{comment} Keep sorted\nb\n{comment} About a.\na");
+        let expected = format!("\n{comment} Keep sorted\n{comment} About a.\na\nb");
+        test_inner!(
+            Strategy::GENERIC,
+            &options_for(path),
+            &input,
+            expected.as_str()
+        );
+    }
+}
+
+#[test]
+fn generic_css_comments() {
+    test_inner!(
+        Strategy::GENERIC,
+        &options_for("style.css"),
+        r#"
+.button {
+  /* keepsorted: keep sorted */
+  margin: 0;
+  color: red;
+  /* Comment about border. */
+  border: none;
+}
+        "#,
+        r#"
+.button {
+  /* keepsorted: keep sorted */
+  /* Comment about border. */
+  border: none;
+  color: red;
+  margin: 0;
+}
+        "#
+    );
+}
+
+#[test]
+fn generic_off_on_region() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+# Keep sorted
+b
+a
+# keepsorted: off
+# Keep sorted
+d
+c
+# keepsorted: on
+# Keep sorted
+f
+e
+        "#,
+        r#"
+# Keep sorted
+a
+b
+# keepsorted: off
+# Keep sorted
+d
+c
+# keepsorted: on
+# Keep sorted
+e
+f
+        "#
+    );
+}
+
+#[test]
+fn generic_off_without_on() {
+    test_inner!(
+        Strategy::GENERIC,
+        r#"
+// keepsorted: off
+// Keep sorted
+b
+a
+        "#,
+        r#"
+// keepsorted: off
+// Keep sorted
+b
+a
+        "#
+    );
+}
+
+#[test]
+fn generic_invalid_option_after_off_region() {
+    let result = common::process_input(
+        Strategy::GENERIC,
+        r#"
+# keepsorted: off
+# keepsorted: on
+# Keep sorted skip_lines=many
+b
+a
+        "#,
+    );
+    let err = result.expect_err("Invalid option was accepted");
+    assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(
+        err.to_string(),
+        "line 4: invalid value 'many' for option 'skip_lines', expected a number"
+    );
+}
+
+#[test]
+fn generic_check_after_off_region() {
+    let lines = "# keepsorted: off\n# Keep sorted\nb\na\n# keepsorted: on\n\n# Keep sorted\nd\nc\n"
+        .split_inclusive('\n')
+        .map(String::from)
+        .collect();
+    let changes: Vec<_> = check_lines(Strategy::GENERIC, lines, &SortOptions::default())
+        .unwrap()
+        .iter()
+        .map(|block| (block.start_line, block.end_line, block.has_duplicates))
+        .collect();
+    assert_eq!(changes, [(8, 9, false)]);
+}
+
+#[test]
+fn generic_crlf() {
+    test_crlf!(
+        Strategy::GENERIC,
+        "# Keep sorted\nb\n# Comment about a.\na\n\nd\nc\n",
+        "# Keep sorted\n# Comment about a.\na\nb\n\nd\nc\n"
+    );
+}
+
+#[test]
+fn generic_bom_is_not_part_of_the_first_line() {
+    test_crlf!(
+        Strategy::GENERIC,
+        "// Keep sorted\nb\na",
+        "// Keep sorted\na\nb"
+    );
+}
@@ -1,12 +1,15 @@
 #[macro_use]
 mod common;
 
-use keepsorted::Strategy::Gitignore;
+use keepsorted::comments::CommentSyntax;
+use keepsorted::options::SortOptions;
+use keepsorted::Strategy;
+use std::path::Path;
 
 #[test]
 fn gitignore_1() {
     test_inner!(
-        Gitignore,
+        Strategy::GITIGNORE,
         r#"
 
 /b
@@ -39,7 +42,7 @@ fn gitignore_1() {
 #[test]
 fn gitignore_ignore_file() {
     test_inner!(
-        Gitignore,
+        Strategy::GITIGNORE,
         r#"
 # keepsorted: ignore file
 
@@ -74,7 +77,7 @@ fn gitignore_ignore_file() {
 #[test]
 fn gitignore_ignore_block_after_header_comment() {
     test_inner!(
-        Gitignore,
+        Strategy::GITIGNORE,
         r#"
 
 /b
@@ -105,3 +108,98 @@ fn gitignore_ignore_block_after_header_comment() {
         "#
     );
 }
+
+#[test]
+fn gitignore_numeric() {
+    test_inner!(
+        Strategy::GITIGNORE,
+        &SortOptions::default().parse("numeric=yes").unwrap(),
+        r#"
+/build10
+/build2
+/build1
+        "#,
+        r#"
+/build1
+/build2
+/build10
+        "#
+    );
+}
+
+#[test]
+fn gitignore_unique() {
+    test_inner!(
+        Strategy::GITIGNORE,
+        &SortOptions::default().parse("unique=yes").unwrap(),
+        r#"
+# Build output.
+/target
+/build
+/target
+        "#,
+        r#"
+# Build output.
+/build
+/target
+        "#
+    );
+}
+
+#[test]
+fn gitignore_off_on_region() {
+    test_inner!(
+        Strategy::GITIGNORE,
+        r#"
+/b
+/a
+# keepsorted: off
+/d
+/c
+# keepsorted: on
+/f
+/e
+        "#,
+        r#"
+/a
+/b
+# keepsorted: off
+/d
+/c
+# keepsorted: on
+/e
+/f
+        "#
+    );
+}
+
+#[test]
+fn gitignore_double_slash_is_a_pattern() {
+    let mut options = SortOptions::default();
+    options.comments = CommentSyntax::for_path(Path::new(".gitignore"));
+    test_inner!(
+        Strategy::GITIGNORE,
+        &options,
+        r#"
+# Build outputs.
+/b
+//a
+*.log
+        "#,
+        r#"
+# Build outputs.
+*.log
+//a
+/b
+        "#
+    );
+}
+
+#[test]
+fn gitignore_crlf() {
+    test_crlf!(
+        Strategy::GITIGNORE,
+        "/b\n/a\n\n# [Rust]\n/d\n/c",
+        "/a\n/b\n\n# [Rust]\n/c\n/d"
+    );
+}
@@ -0,0 +1,177 @@
+use keepsorted::options::SortOptions;
+use keepsorted::strategies::{parse_directive, push_block, Block};
+use keepsorted::{
+    sort_str, Feature, Features, FilePattern, Options, Registry, Result, SortStrategy, Strategy,
+};
+use std::path::Path;
+
+/// Sorts every block of variables separated by empty lines, with the options
+/// of a `# Keep sorted` directive at the top of the block if any.
+struct DotEnv;
+
+impl SortStrategy for DotEnv {
+    fn name(&self) -> &'static str {
+        "dotenv"
+    }
+
+    fn description(&self) -> &'static str {
+        "Variables of `.env` files"
+    }
+
+    fn file_patterns(&self) -> &'static [(FilePattern, Option<Feature>)] {
+        &[(FilePattern::Name(".env"), None)]
+    }
+
+    fn find_blocks(&self, lines: &[String], defaults: &SortOptions) -> Result<Vec<Block>> {
+        let mut blocks = Vec::new();
+        let mut start = 0;
+        let mut options = defaults.clone();
+        for (i, line) in lines.iter().enumerate() {
+            if line.trim().is_empty() {
+                push_block(&mut blocks, lines, start..i, &options, false);
+                start = i + 1;
+                options = defaults.clone();
+            } else if i == start {
+                if let Some(directive) = parse_directive(line, i, defaults)? {
+                    start = i + 1;
+                    options = directive.options;
+                }
+            }
+        }
+        push_block(&mut blocks, lines, start..lines.len(), &options, false);
+        Ok(blocks)
+    }
+}
+
+static DOT_ENV: DotEnv = DotEnv;
+
+#[test]
+fn registry_custom_strategy() {
+    let mut registry = Registry::default();
+    let strategy = registry.register(&DOT_ENV);
+
+    assert_eq!(registry.get("dotenv"), Some(strategy));
+    assert_eq!(registry.get("bazel"), Some(Strategy::BAZEL));
+    assert_eq!(
+        registry.detect(Path::new("app/.env"), &Features::default()),
+        strategy
+    );
+    assert_eq!(
+        registry.detect(Path::new("BUILD"), &Features::default()),
+        Strategy::BAZEL
+    );
+    assert_eq!(
+        registry.detect(Path::new("app.env"), &Features::default()),
+        Strategy::GENERIC
+    );
+    assert_eq!(format!("{:?}", strategy), r#"Strategy("dotenv")"#);
+    assert_eq!(strategy.default_files(), ".env");
+
+    let content = "\
+# Database.
+DB_USER=app
+DB_HOST=localhost
+
+PORT=8080
+# Comment about the host.
+HOST=0.0.0.0
+";
+    let outcome = sort_str(content, &Options::new(strategy)).unwrap();
+    assert_eq!(
+        outcome.text,
+        "\
+DB_HOST=localhost
+# Database.
+DB_USER=app
+
+# Comment about the host.
+HOST=0.0.0.0
+PORT=8080
+"
+    );
+    assert_eq!(outcome.changes.len(), 2);
+    assert_eq!(outcome.changes[1].strategy, strategy);
+}
+
+#[test]
+fn registry_builtin_strategies() {
+    let registry = Registry::default();
+    assert_eq!(registry.strategies(), Strategy::ALL);
+    for strategy in Strategy::ALL {
+        assert_eq!(registry.get(strategy.name()), Some(strategy));
+    }
+    assert_eq!(registry.get("dotenv"), None);
+}
+
+#[test]
+fn registry_custom_strategy_directives() {
+    let mut registry = Registry::default();
+    let strategy = registry.register(&DOT_ENV);
+
+    let content = "\
+# Keep sorted reverse=yes
+A=1
+B=2
+
+# keepsorted: ignore block
+D=4
+C=3
+";
+    let outcome = sort_str(content, &Options::new(strategy)).unwrap();
+    assert_eq!(
+        outcome.text,
+        "\
+# Keep sorted reverse=yes
+B=2
+A=1
+
+# keepsorted: ignore block
+D=4
+C=3
+"
+    );
+
+    let err = sort_str(
+        "# Keep sorted numeric=maybe\nB=2\n",
+        &Options::new(strategy),
+    )
+    .expect_err("Invalid option was accepted");
+    assert_eq!(
+        err.to_string(),
+        "line 1: invalid value 'maybe' for option 'numeric', expected 'yes' or 'no'"
+    );
+}
+
+/// A custom strategy with the name of a built-in one.
+struct OtherGeneric;
+
+impl SortStrategy for OtherGeneric {
+    fn name(&self) -> &'static str {
+        "generic"
+    }
+
+    fn description(&self) -> &'static str {
+        "Nothing"
+    }
+
+    fn find_blocks(&self, _lines: &[String], _defaults: &SortOptions) -> Result<Vec<Block>> {
+        Ok(Vec::new())
+    }
+}
+
+static OTHER_GENERIC: OtherGeneric = OtherGeneric;
+
+#[test]
+fn registry_custom_strategy_identity() {
+    let mut registry = Registry::default();
+    let strategy = registry.register(&OTHER_GENERIC);
+
+    assert_ne!(strategy, Strategy::GENERIC);
+    assert_eq!(registry.get("generic"), Some(strategy));
+    assert_eq!(Strategy::new(&OTHER_GENERIC), strategy);
+    assert_ne!(
+        Strategy::RUST_DERIVE_ALPHABETICAL,
+        Strategy::RUST_DERIVE_CANONICAL
+    );
+    assert_eq!(Strategy::ALL[0], Strategy::GENERIC);
+}
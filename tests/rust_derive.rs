@@ -1,12 +1,12 @@
 #[macro_use]
 mod common;
 
-use keepsorted::Strategy::{RustDeriveAlphabetical, RustDeriveCanonical};
+use keepsorted::Strategy;
 
 #[test]
 fn rust_derive_alphabetical() {
     test_inner!(
-        RustDeriveAlphabetical,
+        Strategy::RUST_DERIVE_ALPHABETICAL,
         r#"
 #[derive(serde::Serialize, C, B, A, Ord, Copy, c, b, a, Serialize)]
 struct Data {}
@@ -21,7 +21,7 @@ struct Data {}
 #[test]
 fn rust_derive_canonical() {
     test_inner!(
-        RustDeriveCanonical,
+        Strategy::RUST_DERIVE_CANONICAL,
         r#"
 #[derive(serde::Serialize, C, B, A, Ord, Copy, c, b, a, Serialize)]
 struct Data {}
@@ -36,7 +36,7 @@ struct Data {}
 #[test]
 fn rust_derive_alphabetical_indented() {
     test_inner!(
-        RustDeriveAlphabetical,
+        Strategy::RUST_DERIVE_ALPHABETICAL,
         r#"
 mod foo {
     #[derive(C, B, A, Ord, Copy)]
@@ -55,7 +55,7 @@ mod foo {
 #[test]
 fn rust_derive_canonical_indented() {
     test_inner!(
-        RustDeriveCanonical,
+        Strategy::RUST_DERIVE_CANONICAL,
         r#"
 mod foo {
     #[derive(C, B, A, Ord, Copy)]
@@ -76,7 +76,7 @@ mod foo {
 #[test]
 fn rust_derive_long_stays_one_line() {
     test_inner!(
-        RustDeriveAlphabetical,
+        Strategy::RUST_DERIVE_ALPHABETICAL,
         //         2         3         4         5         6         7         8         9
         //12345678901234567890123456789012345678901234567890123456789012345678901234567890123456
         r#"
@@ -95,7 +95,7 @@ struct Data {}
 #[test]
 fn rust_derive_long_breaks_into_three_lines() {
     test_inner!(
-        RustDeriveAlphabetical,
+        Strategy::RUST_DERIVE_ALPHABETICAL,
         //         2         3         4         5         6         7         8         9
         //123456789012345678901234567890123456789012345678901234567890123456789012345678901234567
         r#"
@@ -116,7 +116,7 @@ struct Data {}
 #[test]
 fn rust_derive_long_stays_three_lines() {
     test_inner!(
-        RustDeriveAlphabetical,
+        Strategy::RUST_DERIVE_ALPHABETICAL,
         //         2         3         4         5         6         7         8         9         0
         //1234567890123456789012345678901234567890123456789012345678901234567890123456789012345678901
         r#"
@@ -139,7 +139,7 @@ struct Data {}
 #[test]
 fn rust_derive_long_breaks_into_many_lines() {
     test_inner!(
-        RustDeriveAlphabetical,
+        Strategy::RUST_DERIVE_ALPHABETICAL,
         //         2         3         4         5         6         7         8         9         0
         //12345678901234567890123456789012345678901234567890123456789012345678901234567890123456789012
         r#"
@@ -178,7 +178,7 @@ struct Data {}
 #[test]
 fn rust_derive_one_line_ignored() {
     test_inner!(
-        RustDeriveAlphabetical,
+        Strategy::RUST_DERIVE_ALPHABETICAL,
         r#"
 // keepsorted: ignore block
 #[derive(C, B, A, Ord, Copy)]
@@ -195,7 +195,7 @@ struct Data {}
 #[test]
 fn rust_derive_three_lines_ignored() {
     test_inner!(
-        RustDeriveAlphabetical,
+        Strategy::RUST_DERIVE_ALPHABETICAL,
         r#"
 // keepsorted: ignore block
 #[derive(
@@ -216,7 +216,7 @@ struct Data {}
 #[test]
 fn rust_derive_many_lines_ignored() {
     test_inner!(
-        RustDeriveAlphabetical,
+        Strategy::RUST_DERIVE_ALPHABETICAL,
         r#"
 // keepsorted: ignore block
 #[derive(
@@ -245,7 +245,7 @@ struct Data {}
 #[test]
 fn rust_derive_issue_25_1() {
     test_inner!(
-        RustDeriveAlphabetical,
+        Strategy::RUST_DERIVE_ALPHABETICAL,
         r#"
 #[derive(Parser, Debug)] // Some comment.
 struct Data {}
@@ -260,7 +260,7 @@ struct Data {}
 #[test]
 fn rust_derive_issue_25_2() {
     test_inner!(
-        RustDeriveAlphabetical,
+        Strategy::RUST_DERIVE_ALPHABETICAL,
         r#"
 #[derive(Parser, Debug)] // Some comment.
 #[command(about = "description", long_about = None)]
@@ -277,7 +277,7 @@ struct Data {}
 #[test]
 fn rust_derive_issue_25_3() {
     test_inner!(
-        RustDeriveAlphabetical,
+        Strategy::RUST_DERIVE_ALPHABETICAL,
         r#"
 #[derive(Parser, Debug)] // Some comment comment with #[derive(Parser, Debug)].
 #[command(about = "description", long_about = None)]
@@ -290,3 +290,37 @@ struct Data {}
         "#
     );
 }
+
+#[test]
+fn rust_derive_off_on_region() {
+    test_inner!(
+        Strategy::RUST_DERIVE_ALPHABETICAL,
+        r#"
+// keepsorted: off
+#[derive(C, B, A)]
+struct Generated {}
+// keepsorted: on
+
+#[derive(C, B, A)]
+struct Data {}
+        "#,
+        r#"
+// keepsorted: off
+#[derive(C, B, A)]
+struct Generated {}
+// keepsorted: on
+
+#[derive(A, B, C)]
+struct Data {}
+        "#
+    );
+}
+
+#[test]
+fn rust_derive_crlf() {
+    test_crlf!(
+        Strategy::RUST_DERIVE_ALPHABETICAL,
+        "#[derive(\n    C,\n    B,\n    A,\n)]\nstruct Data {}\n",
+        "#[derive(A, B, C)]\nstruct Data {}\n"
+    );
+}